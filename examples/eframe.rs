@@ -23,18 +23,29 @@ fn main() {
 
 pub struct MyApp {
     collector: EventCollector,
+    jump_to: Option<u64>,
 }
 
 impl MyApp {
     fn new(collector: EventCollector) -> Self {
-        Self { collector }
+        Self {
+            collector,
+            jump_to: None,
+        }
     }
 }
 
 impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Some(seq) = egui_tracing::Toasts::new(self.collector.clone()).show(ctx) {
+            self.jump_to = Some(seq);
+        }
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.add(egui_tracing::Logs::new(self.collector.clone()))
+            let mut logs = egui_tracing::Logs::new(self.collector.clone());
+            if let Some(seq) = self.jump_to.take() {
+                logs = logs.jump_to(seq);
+            }
+            ui.add(logs)
         });
     }
 }
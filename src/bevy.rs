@@ -0,0 +1,100 @@
+//! Integration with [Bevy](https://bevyengine.org) and `bevy_egui`.
+//!
+//! [`EguiTracingPlugin`] replaces Bevy's `LogPlugin`, which has to be
+//! disabled:
+//!
+//! ```ignore
+//! App::new()
+//!     .add_plugins(DefaultPlugins.build().disable::<bevy::log::LogPlugin>())
+//!     .add_plugins(EguiTracingPlugin::default())
+//!     .run();
+//! ```
+//!
+//! With Bevy's `trace` feature, events carry the system spans they were
+//! emitted in, shown in the `Span` column of [`Logs`].
+
+use ::bevy::prelude::*;
+use ::bevy_egui::{egui, EguiContexts, EguiPlugin};
+use tracing::{error, Level};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+use crate::{EventCollector, Logs};
+
+/// The collector installed by [`EguiTracingPlugin`].
+#[derive(Resource, Clone, Deref)]
+pub struct TracingCollector(pub EventCollector);
+
+/// Installs a global subscriber that collects events into a
+/// [`TracingCollector`] resource and prints them like Bevy's `LogPlugin`.
+/// With the `log` feature, `log` records are forwarded to it as well.
+pub struct EguiTracingPlugin {
+    /// Filters events like `RUST_LOG`, which overrides it, e.g.
+    /// `wgpu=error,naga=warn`.
+    pub filter: String,
+    /// Default level for targets not mentioned in `filter`.
+    pub level: Level,
+    pub collector: EventCollector,
+    /// Adds [`logs_window`] to draw [`Logs`] in a window.
+    pub window: bool,
+}
+
+impl Default for EguiTracingPlugin {
+    fn default() -> Self {
+        Self {
+            filter: "wgpu=error,naga=warn".to_owned(),
+            level: Level::INFO,
+            collector: EventCollector::default().max_events(10_000),
+            window: true,
+        }
+    }
+}
+
+impl Plugin for EguiTracingPlugin {
+    fn build(&self, app: &mut App) {
+        let env_filter = || {
+            EnvFilter::try_from_default_env()
+                .or_else(|_| EnvFilter::try_new(format!("{},{}", self.level, self.filter)))
+                .unwrap_or_else(|_| EnvFilter::new(self.level.to_string()))
+        };
+        let subscriber = tracing_subscriber::registry()
+            .with(self.collector.clone().with_filter(env_filter()))
+            .with(fmt::layer().with_filter(env_filter()));
+        #[cfg(feature = "log")]
+        let logger_already_set = tracing_log::LogTracer::init().is_err();
+        #[cfg(not(feature = "log"))]
+        let logger_already_set = false;
+        let subscriber_already_set = tracing::subscriber::set_global_default(subscriber).is_err();
+        match (logger_already_set, subscriber_already_set) {
+            (true, true) => error!(
+                "Could not set global logger and tracing subscriber as they are already set. \
+                 Consider disabling LogPlugin."
+            ),
+            (true, false) => error!(
+                "Could not set global logger as it is already set. Consider disabling LogPlugin."
+            ),
+            (false, true) => error!(
+                "Could not set global tracing subscriber as it is already set. \
+                 Consider disabling LogPlugin."
+            ),
+            (false, false) => {}
+        }
+
+        app.insert_resource(TracingCollector(self.collector.clone()));
+        if self.window {
+            if !app.is_plugin_added::<EguiPlugin>() {
+                app.add_plugins(EguiPlugin);
+            }
+            app.add_systems(Update, logs_window);
+        }
+    }
+}
+
+/// Draws [`Logs`] in a window on the primary egui context.
+pub fn logs_window(mut contexts: EguiContexts, collector: Res<TracingCollector>) {
+    egui::Window::new("Logs")
+        .default_size([800.0, 400.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.add(Logs::new(collector.0.clone()));
+        });
+}
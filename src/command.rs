@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+type Handler = Arc<dyn Fn(&[String]) -> Result<String, String> + Send + Sync>;
+type Completer = Arc<dyn Fn(&[String]) -> Vec<String> + Send + Sync>;
+
+/// An app defined command for the `Logs` command line.
+#[derive(Clone)]
+pub struct Command {
+    name: String,
+    help: String,
+    handler: Handler,
+    completer: Option<Completer>,
+}
+
+impl Command {
+    /// `handler` gets the arguments after the command name. Its output, or
+    /// error, is logged below the echoed command.
+    pub fn new(
+        name: impl Into<String>,
+        handler: impl Fn(&[String]) -> Result<String, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            help: String::new(),
+            handler: Arc::new(handler),
+            completer: None,
+        }
+    }
+
+    /// A one-line description shown by `help`, e.g. `spawn <kind> <count>`.
+    pub fn help(self, help: impl Into<String>) -> Self {
+        Self {
+            help: help.into(),
+            ..self
+        }
+    }
+
+    /// Suggests values for the last argument, given all arguments typed so
+    /// far including the incomplete last one.
+    pub fn completions(
+        self,
+        completer: impl Fn(&[String]) -> Vec<String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            completer: Some(Arc::new(completer)),
+            ..self
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn help_text(&self) -> &str {
+        &self.help
+    }
+
+    pub fn run(&self, args: &[String]) -> Result<String, String> {
+        (self.handler)(args)
+    }
+
+    pub fn complete(&self, args: &[String]) -> Vec<String> {
+        self.completer
+            .as_ref()
+            .map(|completer| completer(args))
+            .unwrap_or_default()
+    }
+}
+
+impl Debug for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Command")
+            .field("name", &self.name)
+            .field("help", &self.help)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The commands available in the `Logs` command line, in addition to the
+/// built-in `help`, `clear` and `level`.
+#[derive(Debug, Clone, Default)]
+pub struct CommandRegistry {
+    commands: BTreeMap<String, Command>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `command`, replacing any command with the same name.
+    pub fn register(mut self, command: Command) -> Self {
+        self.commands.insert(command.name.clone(), command);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Command> {
+        self.commands.get(name)
+    }
+
+    pub fn commands(&self) -> impl Iterator<Item = &Command> {
+        self.commands.values()
+    }
+}
+
+/// Splits a command line into words. Double quotes group words and a
+/// backslash escapes the next character.
+pub fn parse(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = None::<String>;
+    let mut quoted = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    word.get_or_insert_with(String::new).push(c);
+                }
+            }
+            '"' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// Completes `prefix` against `candidates`, returning the longest common
+/// prefix of all matches and the matches themselves.
+pub fn complete<'a>(
+    prefix: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> (String, Vec<&'a str>) {
+    let mut matches = candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(prefix))
+        .collect::<Vec<_>>();
+    matches.sort_unstable();
+    matches.dedup();
+    let common = match matches.split_first() {
+        Some((first, rest)) => rest.iter().fold((*first).to_owned(), |common, candidate| {
+            common
+                .chars()
+                .zip(candidate.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect()
+        }),
+        None => prefix.to_owned(),
+    };
+    (common, matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_splits_quotes_and_escapes() {
+        assert_eq!(parse("  level  warn "), ["level", "warn"]);
+        assert_eq!(parse(r#"say "hello world" !"#), ["say", "hello world", "!"]);
+        assert_eq!(parse(r#"a"b c"d"#), ["ab cd"]);
+        assert_eq!(parse(r"open a\ b\\c"), ["open", r"a b\c"]);
+        assert_eq!(parse(r#"say \"hi\""#), ["say", r#""hi""#]);
+        assert_eq!(parse(r"trailing \"), ["trailing"]);
+    }
+
+    #[test]
+    fn parse_keeps_empty_quoted_arguments() {
+        assert_eq!(parse(r#"set name """#), ["set", "name", ""]);
+        assert_eq!(parse(r#""""#), [""]);
+        assert!(parse("   ").is_empty());
+    }
+
+    #[test]
+    fn complete_returns_longest_common_prefix() {
+        let (common, matches) = complete("he", ["help", "hello", "clear", "help"]);
+        assert_eq!(common, "hel");
+        assert_eq!(matches, ["hello", "help"]);
+
+        let (common, matches) = complete("cl", ["help", "clear"]);
+        assert_eq!(common, "clear");
+        assert_eq!(matches, ["clear"]);
+    }
+
+    #[test]
+    fn complete_without_match_keeps_prefix() {
+        let (common, matches) = complete("x", ["help", "clear"]);
+        assert_eq!(common, "x");
+        assert!(matches.is_empty());
+    }
+}
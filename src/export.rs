@@ -0,0 +1,475 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::io::{self, Write};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::time::TimeFormat;
+use crate::tracing::{thread, CollectedEvent, EventCollector, SpanActivity, SpanPhase, SpanRecord};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventFormat {
+    /// Plain text similar to `tracing_subscriber::fmt`.
+    #[default]
+    Text,
+    /// One JSON object per line.
+    JsonLines,
+    /// A Markdown table, e.g. for pasting into issue trackers.
+    Markdown,
+    /// Comma separated values with a header row.
+    Csv,
+    /// A self-contained HTML page with a table colored by level.
+    Html,
+}
+
+impl EventFormat {
+    pub const ALL: [Self; 5] = [
+        Self::Text,
+        Self::JsonLines,
+        Self::Markdown,
+        Self::Csv,
+        Self::Html,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Text => "Text",
+            Self::JsonLines => "JSON Lines",
+            Self::Markdown => "Markdown",
+            Self::Csv => "CSV",
+            Self::Html => "HTML",
+        }
+    }
+
+    /// File extension without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "log",
+            Self::JsonLines => "jsonl",
+            Self::Markdown => "md",
+            Self::Csv => "csv",
+            Self::Html => "html",
+        }
+    }
+
+    pub fn write<'a, W: Write>(
+        self,
+        writer: &mut W,
+        events: impl IntoIterator<Item = &'a CollectedEvent>,
+    ) -> io::Result<()> {
+        match self {
+            Self::Text => {
+                for event in events {
+                    writeln!(writer, "{}", text_line(event))?;
+                }
+            }
+            Self::JsonLines => {
+                for event in events {
+                    serde_json::to_writer(&mut *writer, &json_value(event))?;
+                    writeln!(writer)?;
+                }
+            }
+            Self::Markdown => {
+                writeln!(
+                    writer,
+                    "| Time | Level | Target | Spans | Message | Fields |"
+                )?;
+                writeln!(writer, "| --- | --- | --- | --- | --- | --- |")?;
+                for event in events {
+                    writeln!(
+                        writer,
+                        "| {} | {} | {} | {} | {} | {} |",
+                        timestamp(event),
+                        event.level,
+                        markdown_escape(&event.target),
+                        markdown_escape(&spans(event)),
+                        markdown_escape(event.message().unwrap_or_default()),
+                        markdown_escape(&extra_fields(event)),
+                    )?;
+                }
+            }
+            Self::Csv => {
+                writeln!(writer, "timestamp,level,target,spans,message,fields,count")?;
+                for event in events {
+                    writeln!(
+                        writer,
+                        "{},{},{},{},{},{},{}",
+                        timestamp(event),
+                        event.level,
+                        csv_escape(&event.target),
+                        csv_escape(&spans(event)),
+                        csv_escape(event.message().unwrap_or_default()),
+                        csv_escape(&extra_fields(event)),
+                        event.count,
+                    )?;
+                }
+            }
+            Self::Html => {
+                writeln!(writer, "{HTML_HEAD}")?;
+                for event in events {
+                    let count = if event.count > 1 {
+                        format!(" <b>×{}</b>", event.count)
+                    } else {
+                        String::new()
+                    };
+                    writeln!(
+                        writer,
+                        "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}{}</td><td>{}</td></tr>",
+                        event.level.as_str().to_lowercase(),
+                        timestamp(event),
+                        event.level,
+                        html_escape(&event.target),
+                        html_escape(&spans(event)),
+                        html_escape(event.message().unwrap_or_default()),
+                        count,
+                        html_escape(&extra_fields(event)),
+                    )?;
+                }
+                writeln!(writer, "</table>\n</body>\n</html>")?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn format<'a>(self, events: impl IntoIterator<Item = &'a CollectedEvent>) -> String {
+        let mut buffer = Vec::new();
+        self.write(&mut buffer, events)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(buffer).expect("formatted events are valid UTF-8")
+    }
+}
+
+/// Writes the spans recorded by `collector` as begin/end pairs and `events`
+/// as instant events in the Chrome Trace Event format, which Perfetto and
+/// `chrome://tracing` open. Threads become `tid`s.
+///
+/// Spans are only recorded with
+/// [`EventCollector::record_spans`].
+pub fn write_chrome_trace<'a, W: Write>(
+    writer: &mut W,
+    collector: &EventCollector,
+    events: impl IntoIterator<Item = &'a CollectedEvent>,
+) -> io::Result<()> {
+    let spans = collector
+        .spans()
+        .into_iter()
+        .map(|span| (span.seq, span))
+        .collect::<BTreeMap<_, _>>();
+    let mut activity = BTreeMap::<u64, VecDeque<SpanActivity>>::new();
+    for entry in collector.span_activity() {
+        if spans.contains_key(&entry.span) {
+            activity.entry(entry.thread).or_default().push_back(entry);
+        }
+    }
+    let mut instants = BTreeMap::<u64, VecDeque<&CollectedEvent>>::new();
+    for event in events {
+        instants.entry(event.thread).or_default().push_back(event);
+    }
+    let threads = activity
+        .keys()
+        .chain(instants.keys())
+        .copied()
+        .collect::<BTreeSet<_>>();
+
+    let mut trace = Vec::new();
+    for &tid in &threads {
+        let mut activity = activity.remove(&tid).unwrap_or_default();
+        let mut instants = instants.remove(&tid).unwrap_or_default();
+        // Spans entered on the thread, innermost last.
+        let mut entered = Vec::new();
+        loop {
+            let next_activity = match (activity.front(), instants.front()) {
+                (Some(entry), Some(event)) => happens_before(entry, event),
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            if !next_activity {
+                let event = instants.pop_front().unwrap();
+                trace.push((event.elapsed, instant(event)));
+                continue;
+            }
+            let entry = activity.pop_front().unwrap();
+            let span = &spans[&entry.span];
+            match entry.phase {
+                SpanPhase::Enter => {
+                    entered.push(span);
+                    trace.push((entry.elapsed, begin(span, tid, entry.elapsed)));
+                }
+                SpanPhase::Exit => {
+                    // Exits of spans entered before the recorded activity
+                    // have no begin to pair with.
+                    let Some(index) = entered.iter().rposition(|s| s.seq == span.seq) else {
+                        continue;
+                    };
+                    // An end closes the innermost open span, so spans
+                    // entered later are closed and reopened around it.
+                    let reopened = entered.split_off(index + 1);
+                    for _ in 0..=reopened.len() {
+                        trace.push((entry.elapsed, end(tid, entry.elapsed)));
+                    }
+                    entered.pop();
+                    for span in reopened {
+                        trace.push((entry.elapsed, begin(span, tid, entry.elapsed)));
+                        entered.push(span);
+                    }
+                }
+            }
+        }
+    }
+    // Stable, so entries at the same time keep their order per thread.
+    trace.sort_by_key(|(elapsed, _)| *elapsed);
+
+    let metadata = threads.into_iter().map(|tid| {
+        let name = thread::name(tid).unwrap_or_else(|| format!("thread {tid}"));
+        serde_json::json!({
+            "name": "thread_name",
+            "ph": "M",
+            "pid": 1,
+            "tid": tid,
+            "args": { "name": name },
+        })
+    });
+    let trace_events = metadata
+        .chain(trace.into_iter().map(|(_, value)| value))
+        .collect::<Vec<_>>();
+    serde_json::to_writer(
+        &mut *writer,
+        &serde_json::json!({ "traceEvents": trace_events, "displayTimeUnit": "ms" }),
+    )?;
+    writeln!(writer)
+}
+
+/// Whether `activity` goes before `event` on their thread. At the same time,
+/// an event is placed inside the spans it was emitted in.
+fn happens_before(activity: &SpanActivity, event: &CollectedEvent) -> bool {
+    if activity.elapsed != event.elapsed {
+        return activity.elapsed < event.elapsed;
+    }
+    let inside = event.spans.iter().any(|span| span.id == activity.span);
+    match activity.phase {
+        SpanPhase::Enter => inside,
+        SpanPhase::Exit => !inside,
+    }
+}
+
+fn begin(span: &SpanRecord, tid: u64, elapsed: Duration) -> serde_json::Value {
+    serde_json::json!({
+        "name": span.name,
+        "cat": span.target,
+        "ph": "B",
+        "ts": micros(elapsed),
+        "pid": 1,
+        "tid": tid,
+        "args": span.fields,
+    })
+}
+
+fn end(tid: u64, elapsed: Duration) -> serde_json::Value {
+    serde_json::json!({
+        "ph": "E",
+        "ts": micros(elapsed),
+        "pid": 1,
+        "tid": tid,
+    })
+}
+
+fn instant(event: &CollectedEvent) -> serde_json::Value {
+    let mut args = serde_json::json!(event.fields);
+    args["level"] = event.level.as_str().into();
+    serde_json::json!({
+        "name": event.message().unwrap_or_default(),
+        "cat": event.target,
+        "ph": "i",
+        "s": "t",
+        "ts": micros(event.elapsed),
+        "pid": 1,
+        "tid": event.thread,
+        "args": args,
+    })
+}
+
+fn micros(elapsed: Duration) -> f64 {
+    elapsed.as_secs_f64() * 1e6
+}
+
+fn text_line(event: &CollectedEvent) -> String {
+    let spans = event
+        .spans
+        .iter()
+        .map(|span| format!("{span}:"))
+        .collect::<String>();
+    let mut line = format!(
+        "{} {:>5} {}{}: {}",
+        timestamp(event),
+        event.level,
+        if spans.is_empty() {
+            String::new()
+        } else {
+            format!("{spans} ")
+        },
+        event.target,
+        event.message().unwrap_or_default()
+    );
+    let fields = extra_fields(event);
+    if !fields.is_empty() {
+        line.push(' ');
+        line.push_str(&fields);
+    }
+    if event.count > 1 {
+        line.push_str(&format!(" (×{})", event.count));
+    }
+    line
+}
+
+fn timestamp(event: &CollectedEvent) -> String {
+    TimeFormat::Rfc3339.format_time(&event.time)
+}
+
+fn json_value(event: &CollectedEvent) -> serde_json::Value {
+    let mut value = serde_json::json!({
+        "timestamp": timestamp(event),
+        "level": event.level.as_str(),
+        "target": event.target,
+        "fields": event.fields,
+    });
+    if !event.spans.is_empty() {
+        value["spans"] = event
+            .spans
+            .iter()
+            .map(|span| serde_json::json!({ "name": span.name, "fields": span.fields }))
+            .collect();
+    }
+    if event.count > 1 {
+        value["count"] = event.count.into();
+        value["last_timestamp"] = TimeFormat::Rfc3339.format_time(&event.last_time).into();
+    }
+    value
+}
+
+/// The event's spans, outermost first.
+fn spans(event: &CollectedEvent) -> String {
+    event
+        .spans
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn extra_fields(event: &CollectedEvent) -> String {
+    event
+        .fields
+        .iter()
+        .filter(|(key, _)| key.as_str() != "message")
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const HTML_HEAD: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Logs</title>
+<style>
+body { font-family: monospace; background: #1b1b1b; color: #d0d0d0; }
+table { border-collapse: collapse; }
+th, td { padding: 2px 8px; text-align: left; vertical-align: top; white-space: pre-wrap; }
+tr:nth-child(even) { background: #242424; }
+.trace td:nth-child(2) { color: #75507b; }
+.debug td:nth-child(2) { color: #729fcf; }
+.info td:nth-child(2) { color: #4e9a06; }
+.warn td:nth-child(2) { color: #c4a000; }
+.error td:nth-child(2) { color: #cc0000; }
+</style>
+</head>
+<body>
+<table>
+<tr><th>Time</th><th>Level</th><th>Target</th><th>Spans</th><th>Message</th><th>Fields</th></tr>"#;
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn markdown_escape(value: &str) -> String {
+    value
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::tracing::ManualClock;
+
+    /// Phases and names of the trace events written for `f`, which runs
+    /// without the clock advancing.
+    fn trace(f: impl FnOnce(&EventCollector)) -> Vec<String> {
+        let clock = Arc::new(ManualClock::new(chrono::Local::now()));
+        let collector = EventCollector::new().with_clock(clock).record_spans(true);
+        let subscriber = tracing_subscriber::registry().with(collector.clone());
+        tracing::subscriber::with_default(subscriber, || f(&collector));
+        let mut buffer = Vec::new();
+        write_chrome_trace(&mut buffer, &collector, &collector.events()).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        value["traceEvents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|event| event["ph"] != "M")
+            .map(|event| match event["name"].as_str() {
+                Some(name) => format!("{} {name}", event["ph"].as_str().unwrap()),
+                None => event["ph"].as_str().unwrap().to_owned(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chrome_trace_nests_spans_and_events() {
+        let trace = trace(|_| {
+            let outer = tracing::info_span!("outer");
+            let outer_guard = outer.enter();
+            tracing::info!("a");
+            let inner = tracing::info_span!("inner");
+            let inner_guard = inner.enter();
+            tracing::info!("b");
+            drop(outer_guard);
+            drop(inner_guard);
+            tracing::info!("c");
+        });
+        assert_eq!(
+            trace,
+            ["B outer", "i a", "B inner", "i b", "E", "E", "B inner", "E", "i c"]
+        );
+    }
+
+    #[test]
+    fn chrome_trace_drops_exits_without_enter() {
+        let trace = trace(|collector| {
+            let span = tracing::info_span!("span");
+            let guard = span.enter();
+            collector.clear();
+            drop(guard);
+            tracing::info!("a");
+        });
+        assert_eq!(trace, ["i a"]);
+    }
+}
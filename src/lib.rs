@@ -1,9 +1,13 @@
 #![warn(clippy::all, clippy::cargo)]
 
+#[cfg(feature = "bevy")]
+pub mod bevy;
+pub mod command;
+pub mod export;
 mod string;
-mod time;
+pub mod time;
 pub mod tracing;
 pub mod ui;
 
 pub use self::tracing::EventCollector;
-pub use self::ui::Logs;
+pub use self::ui::{Console, LogStatus, Logs, LogsStyle, Toasts};
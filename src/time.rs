@@ -1,15 +1,108 @@
-use chrono::{DateTime, Local};
+use std::fmt::Write;
+use std::time::Duration;
 
-pub trait DateTimeFormatExt {
-    fn format_short(&self) -> String;
-    fn format_detailed(&self) -> String;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::tracing::CollectedEvent;
+
+/// How timestamps are displayed in the `Logs` time column.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimeFormat {
+    /// Local wall clock time of day.
+    #[default]
+    Local,
+    /// UTC wall clock time of day.
+    Utc,
+    /// Full local date and time with offset.
+    Rfc3339,
+    /// Monotonic time elapsed since the collector was created.
+    SinceStart,
+    /// Time relative to the selected event.
+    SinceSelected,
+    /// Time since the previous visible event.
+    DeltaPrevious,
+    /// A user supplied `strftime` format in local time.
+    Custom(String),
 }
 
-impl DateTimeFormatExt for DateTime<Local> {
-    fn format_short(&self) -> String {
-        self.format("%H:%M:%S%.3f").to_string()
+/// Reference points for the relative [`TimeFormat`]s, as monotonic
+/// [`CollectedEvent::elapsed`] times. Missing references fall back to
+/// [`TimeFormat::Local`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TimeContext {
+    pub selected: Option<Duration>,
+    pub previous: Option<Duration>,
+}
+
+impl TimeFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Local => "Local",
+            Self::Utc => "UTC",
+            Self::Rfc3339 => "RFC 3339",
+            Self::SinceStart => "Since Start",
+            Self::SinceSelected => "Since Selected",
+            Self::DeltaPrevious => "Delta",
+            Self::Custom(_) => "Custom",
+        }
+    }
+
+    pub fn format(&self, event: &CollectedEvent, context: &TimeContext) -> String {
+        let relative = |reference: Option<Duration>| {
+            reference.map(|reference| format_duration(event.elapsed, reference))
+        };
+        let formatted = match self {
+            Self::SinceStart => Some(format_duration(event.elapsed, Duration::ZERO)),
+            Self::SinceSelected => relative(context.selected),
+            Self::DeltaPrevious => relative(context.previous),
+            _ => None,
+        };
+        formatted.unwrap_or_else(|| self.format_time(&event.time))
+    }
+
+    /// Formats a wall clock time. Relative formats fall back to
+    /// [`TimeFormat::Local`].
+    pub fn format_time(&self, time: &DateTime<Local>) -> String {
+        match self {
+            Self::Local | Self::SinceStart | Self::SinceSelected | Self::DeltaPrevious => {
+                time.format("%H:%M:%S%.3f").to_string()
+            }
+            Self::Utc => time.with_timezone(&Utc).format("%H:%M:%S%.3fZ").to_string(),
+            Self::Rfc3339 => time.format("%Y-%m-%dT%H:%M:%S%.f%:z").to_string(),
+            Self::Custom(format) => {
+                let mut formatted = String::new();
+                // `DelayedFormat` reports invalid specifiers as a `fmt::Error`.
+                match write!(formatted, "{}", time.format(format)) {
+                    Ok(()) => formatted,
+                    Err(_) => "invalid format".to_owned(),
+                }
+            }
+        }
     }
-    fn format_detailed(&self) -> String {
-        self.format("%Y-%m-%dT%H:%M:%S%.f%:z").to_string()
+}
+
+/// Formats `time - reference`, always with a sign unless `reference` is zero.
+pub fn format_duration(time: Duration, reference: Duration) -> String {
+    let (sign, duration) = if time < reference {
+        ("-", reference - time)
+    } else if reference.is_zero() {
+        ("", time - reference)
+    } else {
+        ("+", time - reference)
+    };
+    let millis = duration.as_millis();
+    let (hours, minutes, seconds, millis) = (
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000,
+    );
+    if hours > 0 {
+        format!("{sign}{hours}h{minutes:02}m{seconds:02}.{millis:03}s")
+    } else if minutes > 0 {
+        format!("{sign}{minutes}m{seconds:02}.{millis:03}s")
+    } else {
+        format!("{sign}{seconds}.{millis:03}s")
     }
 }
@@ -0,0 +1,145 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use std::time::Duration;
+
+use globset::{Glob, GlobMatcher};
+use tracing::Level;
+
+use super::event::CollectedEvent;
+
+type Callback = Arc<dyn Fn(&Alert) + Send + Sync>;
+
+/// A rule checked against every collected event, e.g. "any ERROR from
+/// `net::*`" or "more than 10 WARN in 5 s".
+#[derive(Clone)]
+pub struct AlertRule {
+    name: String,
+    level: Level,
+    target: Option<GlobMatcher>,
+    threshold: Option<(usize, Duration)>,
+    callback: Option<Callback>,
+    banner: bool,
+}
+
+/// A triggered [`AlertRule`].
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub rule: String,
+    /// The event that triggered the alert.
+    pub event: CollectedEvent,
+    /// Number of matching events within the rule's window, `1` for rules
+    /// without a threshold.
+    pub count: usize,
+}
+
+impl AlertRule {
+    /// A rule matching any event at `ERROR` level.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            level: Level::ERROR,
+            target: None,
+            threshold: None,
+            callback: None,
+            banner: true,
+        }
+    }
+
+    /// Matches events at `level` or more severe.
+    pub fn level(self, level: Level) -> Self {
+        Self { level, ..self }
+    }
+
+    /// Matches events whose target matches `glob`.
+    pub fn target(self, glob: Glob) -> Self {
+        Self {
+            target: Some(glob.compile_matcher()),
+            ..self
+        }
+    }
+
+    /// Only triggers once more than `count` matching events arrived within
+    /// `window`.
+    pub fn more_than(self, count: usize, window: Duration) -> Self {
+        Self {
+            threshold: Some((count, window)),
+            ..self
+        }
+    }
+
+    /// Calls `callback` whenever the rule triggers. It runs on the thread
+    /// that emitted the event.
+    pub fn on_alert(self, callback: impl Fn(&Alert) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Some(Arc::new(callback)),
+            ..self
+        }
+    }
+
+    /// Whether `Logs` shows a banner for triggered alerts. Defaults to `true`.
+    pub fn banner(self, banner: bool) -> Self {
+        Self { banner, ..self }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn shows_banner(&self) -> bool {
+        self.banner
+    }
+
+    pub(crate) fn matches(&self, event: &CollectedEvent) -> bool {
+        event.level <= self.level
+            && self
+                .target
+                .as_ref()
+                .is_none_or(|target| target.is_match(&event.target))
+    }
+
+    /// Records a matching event at `elapsed`, returning the number of
+    /// matching events if the rule triggers.
+    pub(crate) fn record(
+        &self,
+        recent: &mut VecDeque<Duration>,
+        elapsed: Duration,
+    ) -> Option<usize> {
+        let Some((count, window)) = self.threshold else {
+            return Some(1);
+        };
+        recent.push_back(elapsed);
+        while recent
+            .front()
+            .is_some_and(|time| elapsed.saturating_sub(*time) > window)
+        {
+            recent.pop_front();
+        }
+        if recent.len() > count {
+            let count = recent.len();
+            // Start counting again so the rule doesn't trigger on every event.
+            recent.clear();
+            Some(count)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn notify(&self, alert: &Alert) {
+        if let Some(callback) = &self.callback {
+            callback(alert);
+        }
+    }
+}
+
+impl Debug for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlertRule")
+            .field("name", &self.name)
+            .field("level", &self.level)
+            .field("target", &self.target.as_ref().map(GlobMatcher::glob))
+            .field("threshold", &self.threshold)
+            .field("banner", &self.banner)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+
+/// The time source of an [`EventCollector`](super::EventCollector).
+///
+/// `elapsed` must be monotonic; it is used to order and measure events even
+/// when the wall clock returned by `now` jumps.
+pub trait Clock: Debug + Send + Sync {
+    /// The current wall clock time.
+    fn now(&self) -> DateTime<Local>;
+    /// Monotonic time elapsed since the clock was created.
+    fn elapsed(&self) -> Duration;
+}
+
+#[derive(Debug)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when told to, for deterministic tests.
+#[derive(Debug)]
+pub struct ManualClock {
+    state: Mutex<(DateTime<Local>, Duration)>,
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Local>) -> Self {
+        Self {
+            state: Mutex::new((start, Duration::ZERO)),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.0 += chrono::Duration::from_std(duration).unwrap();
+        state.1 += duration;
+    }
+
+    /// Moves the wall clock without affecting the monotonic time.
+    pub fn set_wall_clock(&self, now: DateTime<Local>) {
+        self.state.lock().unwrap().0 = now;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Local> {
+        self.state.lock().unwrap().0
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.state.lock().unwrap().1
+    }
+}
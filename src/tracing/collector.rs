@@ -1,13 +1,65 @@
-use std::sync::{Arc, Mutex};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 
+use chrono::{DateTime, Local};
+use tracing::callsite::Identifier;
+use tracing::span::{Attributes, Id, Record};
 use tracing::{Event, Level, Subscriber};
 #[cfg(feature = "log")]
-use tracing_log::NormalizeEvent;
+use tracing_log::{AsLog, NormalizeEvent};
 use tracing_subscriber::layer::Context;
 use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::Layer;
 
-use super::event::CollectedEvent;
+use super::alert::{Alert, AlertRule};
+use super::clock::{Clock, SystemClock};
+use super::event::{CollectedEvent, CollectedSpan, FieldVisitor};
+use super::flight::FlightRecorder;
+use super::limit::{Limit, LimitState};
+use super::span::{SpanActivity, SpanLog, SpanPhase, SpanRecord};
+use super::thread;
+use super::timing::SpanStats;
+
+/// A span's id and fields, stored in its extensions.
+struct SpanFields {
+    id: u64,
+    fields: BTreeMap<String, String>,
+}
+
+/// A recorded span's [`SpanRecord`] sequence number and timing, stored in
+/// its extensions.
+struct RecordedSpan {
+    seq: u64,
+    opened: Duration,
+    busy: Duration,
+    /// When the span was entered while no thread was inside it.
+    entered: Option<Duration>,
+    /// Number of threads inside the span.
+    depth: usize,
+}
+
+impl RecordedSpan {
+    fn enter(&mut self, now: Duration) {
+        if self.depth == 0 {
+            self.entered = Some(now);
+        }
+        self.depth += 1;
+    }
+
+    fn exit(&mut self, now: Duration) {
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth == 0 {
+            if let Some(entered) = self.entered.take() {
+                self.busy += now.saturating_sub(entered);
+            }
+        }
+    }
+}
+
+/// Banners kept until dismissed, dropping the oldest.
+const MAX_ALERTS: usize = 20;
 
 #[derive(Clone, Debug)]
 pub enum AllowedTargets {
@@ -19,7 +71,34 @@ pub enum AllowedTargets {
 pub struct EventCollector {
     allowed_targets: AllowedTargets,
     level: Level,
-    events: Arc<Mutex<Vec<CollectedEvent>>>,
+    max_events: Option<usize>,
+    deduplicate: bool,
+    limits: Arc<Vec<Limit>>,
+    alert_rules: Arc<Vec<AlertRule>>,
+    flight_recorder: Option<FlightRecorder>,
+    record_spans: bool,
+    events: Arc<Mutex<Buffer>>,
+    spans: Arc<Mutex<SpanLog>>,
+    next_span_id: Arc<AtomicU64>,
+    clock: Arc<dyn Clock>,
+    start: DateTime<Local>,
+}
+
+#[derive(Debug, Default)]
+struct Buffer {
+    events: VecDeque<CollectedEvent>,
+    /// Approximate memory used by `events`, in bytes.
+    bytes: usize,
+    next_seq: u64,
+    /// Incremented whenever an event is added or updated.
+    revision: u64,
+    /// Events pushed per level, including folded and dropped ones.
+    level_counts: HashMap<Level, u64>,
+    limits: HashMap<(usize, Option<Identifier>), LimitState>,
+    /// Recent matching event times per alert rule.
+    alert_windows: Vec<VecDeque<Duration>>,
+    /// Alerts shown as banners until dismissed.
+    alerts: VecDeque<Alert>,
 }
 
 impl EventCollector {
@@ -38,37 +117,484 @@ impl EventCollector {
         }
     }
 
+    /// Keeps at most `max_events`, evicting the oldest events first.
+    pub fn max_events(self, max_events: usize) -> Self {
+        Self {
+            max_events: Some(max_events),
+            ..self
+        }
+    }
+
+    /// Folds identical consecutive events into one with a repeat count.
+    /// Folded repeats do not count against [`limit`](Self::limit)s.
+    pub fn deduplicate(self, deduplicate: bool) -> Self {
+        Self {
+            deduplicate,
+            ..self
+        }
+    }
+
+    /// Rate limits or samples matching targets. The first matching limit
+    /// applies. Dropped events are counted in a synthetic
+    /// "N events suppressed" event, and are still checked against alert
+    /// rules.
+    pub fn limit(mut self, limit: Limit) -> Self {
+        Arc::make_mut(&mut self.limits).push(limit);
+        self
+    }
+
+    /// Checks every collected event against `rule`.
+    pub fn alert(mut self, rule: AlertRule) -> Self {
+        Arc::make_mut(&mut self.alert_rules).push(rule);
+        self
+    }
+
+    /// Also passes events below the collector's level to `recorder`, which
+    /// keeps them hidden unless it writes them to disk.
+    pub fn flight_recorder(self, recorder: FlightRecorder) -> Self {
+        Self {
+            flight_recorder: Some(recorder),
+            ..self
+        }
+    }
+
+    /// Records span lifetimes and every enter and exit, e.g. for
+    /// [`write_chrome_trace`](crate::export::write_chrome_trace), and
+    /// aggregates the [`span_stats`](Self::span_stats).
+    /// [`max_events`](Self::max_events) also limits the spans and the
+    /// enters and exits kept.
+    pub fn record_spans(self, record_spans: bool) -> Self {
+        Self {
+            record_spans,
+            ..self
+        }
+    }
+
+    /// Recorded spans, oldest first.
+    pub fn spans(&self) -> Vec<SpanRecord> {
+        self.spans.lock().unwrap().records.iter().cloned().collect()
+    }
+
+    /// Timings of closed spans per span name, sorted by name.
+    pub fn span_stats(&self) -> Vec<SpanStats> {
+        self.spans.lock().unwrap().stats.values().cloned().collect()
+    }
+
+    /// Whether spans are recorded, see [`record_spans`](Self::record_spans).
+    pub fn records_spans(&self) -> bool {
+        self.record_spans
+    }
+
+    /// Recorded enters and exits of spans, oldest first.
+    pub fn span_activity(&self) -> Vec<SpanActivity> {
+        self.spans
+            .lock()
+            .unwrap()
+            .activity
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    /// The most verbose level either shown or flight recorded.
+    fn recorded_level(&self) -> Level {
+        self.flight_recorder
+            .as_ref()
+            .map_or(self.level, |recorder| {
+                recorder.level_limit().max(self.level)
+            })
+    }
+
+    /// Triggered alerts that want a banner and were not dismissed yet.
+    pub fn alerts(&self) -> Vec<Alert> {
+        self.events.lock().unwrap().alerts.iter().cloned().collect()
+    }
+
+    pub fn dismiss_alert(&self, alert: &Alert) {
+        self.events
+            .lock()
+            .unwrap()
+            .alerts
+            .retain(|a| a.rule != alert.rule || a.event.seq != alert.event.seq);
+    }
+
+    pub fn dismiss_alerts(&self) {
+        self.events.lock().unwrap().alerts.clear();
+    }
+
+    /// Replaces the time source, e.g. with a
+    /// [`ManualClock`](super::clock::ManualClock) in tests.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            start: clock.now(),
+            clock,
+            ..self
+        }
+    }
+
+    /// The time at which this collector was created.
+    pub fn start_time(&self) -> DateTime<Local> {
+        self.start
+    }
+
     pub fn events(&self) -> Vec<CollectedEvent> {
-        self.events.lock().unwrap().clone()
+        self.events.lock().unwrap().events.iter().cloned().collect()
+    }
+
+    /// Adds an event that was not emitted through `tracing`. Limits,
+    /// deduplication and alert rules apply as usual.
+    pub fn record(&self, level: Level, target: &str, message: impl Into<String>) {
+        let event = CollectedEvent::synthetic(level, target, message.into(), self.clock.as_ref());
+        self.push(event);
     }
 
+    /// Installs a clone of this collector as the global `log` logger.
+    #[cfg(feature = "log")]
+    pub fn init_log(&self) -> Result<(), log::SetLoggerError> {
+        log::set_boxed_logger(Box::new(self.clone()))?;
+        log::set_max_level(self.recorded_level().as_log().to_level_filter());
+        Ok(())
+    }
+
+    /// Events with a sequence number of at least `seq`, without copying
+    /// older ones.
+    pub fn events_since(&self, seq: u64) -> Vec<CollectedEvent> {
+        let buffer = self.events.lock().unwrap();
+        let start = buffer.events.partition_point(|event| event.seq < seq);
+        buffer.events.range(start..).cloned().collect()
+    }
+
+    /// The sequence number the next collected event will get.
+    pub fn next_seq(&self) -> u64 {
+        self.events.lock().unwrap().next_seq
+    }
+
+    /// The revision of the latest added or updated event.
+    pub fn revision(&self) -> u64 {
+        self.events.lock().unwrap().revision
+    }
+
+    /// Events added or updated after `revision`, in sequence order. Unlike
+    /// [`events_since`](Self::events_since) this includes events that
+    /// repeats were folded into and updated "N events suppressed" rows.
+    pub fn events_changed_since(&self, revision: u64) -> Vec<CollectedEvent> {
+        let buffer = self.events.lock().unwrap();
+        // New events and the last one, which repeats fold into, are at the
+        // end; suppressed rows can be anywhere.
+        let changed = buffer
+            .events
+            .iter()
+            .rev()
+            .take_while(|event| event.revision > revision)
+            .count();
+        let start = buffer.events.len() - changed;
+        let mut events = buffer
+            .limits
+            .values()
+            .filter_map(|state| state.suppressed_seq)
+            .filter_map(|seq| buffer.events.binary_search_by_key(&seq, |e| e.seq).ok())
+            .filter(|row| *row < start && buffer.events[*row].revision > revision)
+            .map(|row| buffer.events[row].clone())
+            .collect::<Vec<_>>();
+        events.sort_by_key(|event| event.seq);
+        events.extend(buffer.events.range(start..).cloned());
+        events
+    }
+
+    /// Number of events at `level` pushed since the collector was created,
+    /// including folded repeats and events dropped by limits. Clearing does
+    /// not reset it.
+    pub fn level_count(&self, level: Level) -> u64 {
+        let buffer = self.events.lock().unwrap();
+        buffer.level_counts.get(&level).copied().unwrap_or_default()
+    }
+
+    pub(crate) fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// Monotonic time since the collector's clock started.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    /// Approximate memory used by the collected events, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.events.lock().unwrap().bytes
+    }
+
+    /// The limit set with [`max_events`](Self::max_events).
+    pub fn max_events_limit(&self) -> Option<usize> {
+        self.max_events
+    }
+
+    /// Removes all events and closed spans. Sequence numbers keep counting
+    /// up.
     pub fn clear(&self) {
-        let mut events = self.events.lock().unwrap();
-        *events = Vec::new();
+        let mut buffer = self.events.lock().unwrap();
+        buffer.events = VecDeque::new();
+        buffer.bytes = 0;
+        drop(buffer);
+        self.spans.lock().unwrap().clear();
     }
 
     fn collect(&self, event: CollectedEvent) {
-        if event.level <= self.level {
-            let should_collect = match self.allowed_targets {
+        let should_collect = event.level <= self.level
+            && match self.allowed_targets {
                 AllowedTargets::All => true,
                 AllowedTargets::Selected(ref selection) => selection
                     .iter()
                     .any(|target| event.target.starts_with(target)),
             };
-            if should_collect {
-                self.events.lock().unwrap().push(event);
+        if should_collect {
+            self.push(event);
+        } else if let Some(recorder) = &self.flight_recorder {
+            recorder.record(&event);
+        }
+    }
+
+    pub(crate) fn push(&self, event: CollectedEvent) {
+        if let Some(recorder) = &self.flight_recorder {
+            recorder.record(&event);
+        }
+        self.push_locked(self.events.lock().unwrap(), event);
+    }
+
+    /// Like [`push`](Self::push), but skips what stays locked for `timeout`,
+    /// e.g. in a panic hook while the panicking thread holds a lock. Returns
+    /// whether the event was collected.
+    pub(crate) fn try_push(&self, event: CollectedEvent, timeout: Duration) -> bool {
+        if let Some(recorder) = &self.flight_recorder {
+            recorder.try_record(&event, timeout);
+        }
+        match thread::lock_within(&self.events, timeout) {
+            Some(buffer) => {
+                self.push_locked(buffer, event);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Like [`events`](Self::events), but gives up if the buffer stays
+    /// locked for `timeout`.
+    pub(crate) fn try_events(&self, timeout: Duration) -> Option<Vec<CollectedEvent>> {
+        let buffer = thread::lock_within(&self.events, timeout)?;
+        Some(buffer.events.iter().cloned().collect())
+    }
+
+    fn push_locked(&self, mut buffer: MutexGuard<'_, Buffer>, mut event: CollectedEvent) {
+        let alerts = {
+            let buffer = &mut *buffer;
+            *buffer.level_counts.entry(event.level).or_default() += event.count;
+            let dropped = match buffer.events.back_mut() {
+                Some(last) if self.deduplicate && last.same_as(&event) => {
+                    last.fold(&event);
+                    buffer.revision += 1;
+                    last.revision = buffer.revision;
+                    None
+                }
+                _ => match self.admit(buffer, &event) {
+                    Ok(()) => {
+                        buffer.push(event, self.max_events);
+                        None
+                    }
+                    Err(seq) => {
+                        // Alerts on dropped events point at the row counting them.
+                        event.seq = seq;
+                        Some(event)
+                    }
+                },
+            };
+            let Buffer {
+                events,
+                alert_windows,
+                alerts,
+                ..
+            } = buffer;
+            match dropped.as_ref().or(events.back()) {
+                Some(event) => self.check_alerts(alert_windows, alerts, event),
+                None => Vec::new(),
+            }
+        };
+        // Callbacks may emit events themselves, so the buffer must be unlocked.
+        drop(buffer);
+        for (rule, alert) in alerts {
+            rule.notify(&alert);
+        }
+    }
+
+    /// Checks the latest event against the alert rules, adding banners to
+    /// `banners`.
+    fn check_alerts(
+        &self,
+        alert_windows: &mut Vec<VecDeque<Duration>>,
+        banners: &mut VecDeque<Alert>,
+        event: &CollectedEvent,
+    ) -> Vec<(&AlertRule, Alert)> {
+        let mut alerts = Vec::new();
+        alert_windows.resize_with(self.alert_rules.len(), VecDeque::new);
+        for (rule, recent) in self.alert_rules.iter().zip(alert_windows) {
+            if !rule.matches(event) {
+                continue;
+            }
+            if let Some(count) = rule.record(recent, event.last_elapsed) {
+                let alert = Alert {
+                    rule: rule.name().to_owned(),
+                    event: event.clone(),
+                    count,
+                };
+                if rule.shows_banner() {
+                    banners.push_back(alert.clone());
+                    if banners.len() > MAX_ALERTS {
+                        banners.pop_front();
+                    }
+                }
+                alerts.push((rule, alert));
+            }
+        }
+        alerts
+    }
+
+    /// Applies the first matching limit, recording suppressed events. Returns
+    /// the sequence number of the row counting `event` if it was dropped.
+    fn admit(&self, buffer: &mut Buffer, event: &CollectedEvent) -> Result<(), u64> {
+        let Some((index, limit)) = self
+            .limits
+            .iter()
+            .enumerate()
+            .find(|(_, limit)| limit.matches(&event.target))
+        else {
+            return Ok(());
+        };
+        let key = (
+            index,
+            limit.is_per_callsite().then(|| event.callsite.clone()),
+        );
+        let now = self.clock.elapsed();
+        let state = buffer
+            .limits
+            .entry(key.clone())
+            .or_insert_with(|| LimitState::new(limit, now));
+        if state.admit(limit, now) {
+            return Ok(());
+        }
+
+        let count = state.suppressed;
+        let row = state
+            .suppressed_seq
+            .and_then(|seq| buffer.events.binary_search_by_key(&seq, |e| e.seq).ok());
+        match row {
+            Some(row) => {
+                let event = &mut buffer.events[row];
+                buffer.bytes -= size(event);
+                event.set_suppressed(count);
+                buffer.bytes += size(event);
+                buffer.revision += 1;
+                event.revision = buffer.revision;
+                Err(event.seq)
+            }
+            None => {
+                let seq = buffer.push(CollectedEvent::suppressed(event, count), self.max_events);
+                if let Some(state) = buffer.limits.get_mut(&key) {
+                    state.suppressed_seq = Some(seq);
+                }
+                Err(seq)
             }
         }
     }
 }
 
+impl Buffer {
+    /// Appends `event`, returning its sequence number.
+    fn push(&mut self, mut event: CollectedEvent, max_events: Option<usize>) -> u64 {
+        let seq = self.next_seq;
+        event.seq = seq;
+        self.next_seq += 1;
+        self.revision += 1;
+        event.revision = self.revision;
+        self.bytes += size(&event);
+        self.events.push_back(event);
+        if let Some(max_events) = max_events {
+            while self.events.len() > max_events {
+                if let Some(evicted) = self.events.pop_front() {
+                    self.bytes -= size(&evicted);
+                }
+            }
+        }
+        seq
+    }
+}
+
+fn size(event: &CollectedEvent) -> usize {
+    std::mem::size_of::<CollectedEvent>() + event.heap_size()
+}
+
 impl Default for EventCollector {
     fn default() -> Self {
+        let clock = Arc::new(SystemClock::default());
         Self {
             allowed_targets: AllowedTargets::All,
-            events: Arc::new(Mutex::new(Vec::new())),
+            events: Arc::new(Mutex::new(Buffer::default())),
             level: Level::TRACE, // capture everything by default.
+            max_events: None,
+            deduplicate: false,
+            limits: Arc::default(),
+            alert_rules: Arc::default(),
+            flight_recorder: None,
+            record_spans: false,
+            spans: Arc::default(),
+            next_span_id: Arc::default(),
+            start: clock.now(),
+            clock,
+        }
+    }
+}
+
+/// Lets the collector be used as a `log` backend without a `tracing`
+/// subscriber, see [`EventCollector::init_log`].
+#[cfg(feature = "log")]
+impl log::Log for EventCollector {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.recorded_level().as_log()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if log::Log::enabled(self, record.metadata()) {
+            self.collect(CollectedEvent::from_log(record, self.clock.as_ref()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+impl EventCollector {
+    fn span_activity_at<S>(&self, id: &Id, phase: SpanPhase, ctx: &Context<'_, S>)
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        let Some(recorded) = extensions.get_mut::<RecordedSpan>() else {
+            return;
+        };
+        let now = self.clock.elapsed();
+        match phase {
+            SpanPhase::Enter => recorded.enter(now),
+            SpanPhase::Exit => recorded.exit(now),
         }
+        let activity = SpanActivity {
+            span: recorded.seq,
+            phase,
+            thread: thread::current_id(),
+            elapsed: now,
+        };
+        self.spans
+            .lock()
+            .unwrap()
+            .push_activity(activity, self.max_events);
     }
 }
 
@@ -76,7 +602,99 @@ impl<S> Layer<S> for EventCollector
 where
     S: Subscriber + for<'a> LookupSpan<'a>,
 {
-    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = BTreeMap::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        let span_id = if self.record_spans && *span.metadata().level() <= self.recorded_level() {
+            let parent = span.parent().and_then(|parent| {
+                parent
+                    .extensions()
+                    .get::<RecordedSpan>()
+                    .map(|recorded| recorded.seq)
+            });
+            let opened = self.clock.elapsed();
+            // Take the id under the lock so records stay ordered by seq.
+            let mut spans = self.spans.lock().unwrap();
+            let seq = self.next_span_id.fetch_add(1, Ordering::Relaxed);
+            spans.open(
+                SpanRecord {
+                    seq,
+                    parent,
+                    name: span.name().to_owned(),
+                    target: span.metadata().target().to_owned(),
+                    level: *span.metadata().level(),
+                    fields: fields.clone(),
+                    thread: thread::current_id(),
+                    opened,
+                    closed: None,
+                    busy: Duration::ZERO,
+                },
+                self.max_events,
+            );
+            drop(spans);
+            span.extensions_mut().insert(RecordedSpan {
+                seq,
+                opened,
+                busy: Duration::ZERO,
+                entered: None,
+                depth: 0,
+            });
+            seq
+        } else {
+            self.next_span_id.fetch_add(1, Ordering::Relaxed)
+        };
+        span.extensions_mut().insert(SpanFields {
+            id: span_id,
+            fields,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            if let Some(span_fields) = extensions.get_mut::<SpanFields>() {
+                values.record(&mut FieldVisitor(&mut span_fields.fields));
+            }
+            if let Some(recorded) = extensions.get_mut::<RecordedSpan>() {
+                if let Some(record) = self.spans.lock().unwrap().get_mut(recorded.seq) {
+                    values.record(&mut FieldVisitor(&mut record.fields));
+                }
+            }
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        self.span_activity_at(id, SpanPhase::Enter, &ctx);
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        self.span_activity_at(id, SpanPhase::Exit, &ctx);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(recorded) = extensions.get::<RecordedSpan>() else {
+            return;
+        };
+        let now = self.clock.elapsed();
+        let idle = now
+            .saturating_sub(recorded.opened)
+            .saturating_sub(recorded.busy);
+        let mut spans = self.spans.lock().unwrap();
+        spans.close(span.name(), recorded.busy, idle);
+        if let Some(record) = spans.get_mut(recorded.seq) {
+            record.closed = Some(now);
+            record.busy = recorded.busy;
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
         #[cfg(feature = "log")]
         let normalized_meta = event.normalized_metadata();
         #[cfg(feature = "log")]
@@ -84,6 +702,223 @@ where
         #[cfg(not(feature = "log"))]
         let meta = event.metadata();
 
-        self.collect(CollectedEvent::new(event, meta));
+        let mut collected = CollectedEvent::new(event, meta, self.clock.as_ref());
+        if collected.level <= self.recorded_level() {
+            if let Some(scope) = ctx.event_scope(event) {
+                collected.spans = scope
+                    .from_root()
+                    .map(|span| {
+                        // Spans created before the collector was added have
+                        // no id yet.
+                        if span.extensions().get::<SpanFields>().is_none() {
+                            span.extensions_mut().insert(SpanFields {
+                                id: self.next_span_id.fetch_add(1, Ordering::Relaxed),
+                                fields: BTreeMap::new(),
+                            });
+                        }
+                        let extensions = span.extensions();
+                        let span_fields = extensions.get::<SpanFields>();
+                        CollectedSpan {
+                            name: span.name().to_owned(),
+                            fields: span_fields
+                                .map(|span_fields| span_fields.fields.clone())
+                                .unwrap_or_default(),
+                            id: span_fields.map_or(0, |span_fields| span_fields.id),
+                        }
+                    })
+                    .collect();
+            }
+        }
+        self.collect(collected);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::tracing::ManualClock;
+
+    fn collector(clock: &Arc<ManualClock>) -> EventCollector {
+        EventCollector::new().with_clock(clock.clone())
+    }
+
+    fn seqs(events: &[CollectedEvent]) -> Vec<u64> {
+        events.iter().map(|event| event.seq).collect()
+    }
+
+    #[test]
+    fn seqs_survive_eviction_and_clear() {
+        let clock = Arc::new(ManualClock::new(Local::now()));
+        let collector = collector(&clock).max_events(2);
+        for message in ["a", "b", "c"] {
+            collector.record(Level::INFO, "test", message);
+        }
+        assert_eq!(seqs(&collector.events()), [1, 2]);
+        assert_eq!(seqs(&collector.events_since(2)), [2]);
+        assert_eq!(collector.next_seq(), 3);
+
+        collector.clear();
+        collector.record(Level::INFO, "test", "d");
+        assert_eq!(seqs(&collector.events()), [3]);
+    }
+
+    #[test]
+    fn offsets_stay_monotonic_when_wall_clock_jumps() {
+        let start = Local::now();
+        let clock = Arc::new(ManualClock::new(start));
+        let collector = collector(&clock);
+        clock.advance(Duration::from_secs(1));
+        collector.record(Level::INFO, "test", "a");
+        clock.set_wall_clock(start - chrono::Duration::hours(1));
+        clock.advance(Duration::from_secs(1));
+        collector.record(Level::INFO, "test", "b");
+
+        let events = collector.events();
+        assert!(events[1].time < events[0].time);
+        assert_eq!(events[0].elapsed, Duration::from_secs(1));
+        assert_eq!(events[1].elapsed, Duration::from_secs(2));
+        assert_eq!(collector.elapsed(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn repeats_fold_before_limits() {
+        let clock = Arc::new(ManualClock::new(Local::now()));
+        let collector = collector(&clock)
+            .deduplicate(true)
+            .limit(Limit::rate("test", 1.0, 1));
+        for _ in 0..3 {
+            collector.record(Level::INFO, "test", "a");
+        }
+        let events = collector.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].count, 3);
+    }
+
+    #[test]
+    fn repeats_fold_across_new_span_instances() {
+        let clock = Arc::new(ManualClock::new(Local::now()));
+        let collector = collector(&clock).deduplicate(true);
+        let subscriber = tracing_subscriber::registry().with(collector.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            for frame in 0..5 {
+                let _span = tracing::info_span!("frame", kind = "update").entered();
+                tracing::warn!("same warning");
+                if frame == 4 {
+                    let _span = tracing::info_span!("frame", kind = "render").entered();
+                    tracing::warn!("same warning");
+                }
+            }
+        });
+        let events = collector.events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].count, 5);
+        assert_eq!(events[1].count, 1);
+    }
+
+    #[test]
+    fn alerts_see_dropped_events() {
+        let clock = Arc::new(ManualClock::new(Local::now()));
+        let collector = collector(&clock)
+            .limit(Limit::rate("test", 1.0, 1))
+            .alert(AlertRule::new("errors").more_than(2, Duration::from_secs(10)));
+        for message in ["a", "b", "c"] {
+            collector.record(Level::ERROR, "test", message);
+        }
+        let events = collector.events();
+        assert_eq!(events.len(), 2);
+        let alerts = collector.alerts();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].event.message(), Some("c"));
+        assert_eq!(alerts[0].event.seq, events[1].seq);
+    }
+
+    #[test]
+    fn memory_usage_follows_push_and_eviction() {
+        let clock = Arc::new(ManualClock::new(Local::now()));
+        let collector = collector(&clock)
+            .max_events(2)
+            .limit(Limit::rate("limited", 1.0, 1));
+        let walk = |collector: &EventCollector| {
+            let buffer = collector.events.lock().unwrap();
+            buffer.events.iter().map(size).sum::<usize>()
+        };
+        for message in ["a", "bb", "ccc"] {
+            collector.record(Level::INFO, "test", message);
+            assert_eq!(collector.memory_usage(), walk(&collector));
+        }
+        for _ in 0..12 {
+            collector.record(Level::INFO, "limited", "d");
+            assert_eq!(collector.memory_usage(), walk(&collector));
+        }
+        let subscriber = tracing_subscriber::registry().with(collector.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("frame", number = 1, name = "update").entered();
+            tracing::info!(target: "test", "e");
+            let event = collector.events().pop().unwrap();
+            let without_spans = CollectedEvent {
+                spans: Vec::new(),
+                ..event.clone()
+            };
+            let span_size = std::mem::size_of::<CollectedSpan>()
+                + "frame".len()
+                + 2 * std::mem::size_of::<(String, String)>()
+                + "number1nameupdate".len();
+            assert!(event.heap_size() >= without_spans.heap_size() + span_size);
+            assert_eq!(collector.memory_usage(), walk(&collector));
+        });
+        collector.clear();
+        assert_eq!(collector.memory_usage(), 0);
+    }
+
+    #[test]
+    fn changes_include_folds_and_suppressed_rows() {
+        let clock = Arc::new(ManualClock::new(Local::now()));
+        let collector = collector(&clock)
+            .deduplicate(true)
+            .limit(Limit::rate("limited", 1.0, 1));
+        collector.record(Level::WARN, "limited", "a");
+        collector.record(Level::WARN, "limited", "b");
+        collector.record(Level::INFO, "test", "c");
+        let revision = collector.revision();
+
+        collector.record(Level::INFO, "test", "c");
+        collector.record(Level::WARN, "limited", "d");
+        let changed = collector.events_changed_since(revision);
+        assert_eq!(seqs(&changed), [1, 2]);
+        assert_eq!(changed[0].message(), Some("2 events suppressed"));
+        assert_eq!(changed[1].count, 2);
+        assert!(collector
+            .events_changed_since(collector.revision())
+            .is_empty());
+        assert_eq!(collector.level_count(Level::WARN), 3);
+        assert_eq!(collector.level_count(Level::INFO), 2);
+    }
+
+    #[test]
+    fn try_push_waits_for_other_threads_and_skips_own_lock() {
+        let clock = Arc::new(ManualClock::new(Local::now()));
+        let collector = collector(&clock);
+        let event = CollectedEvent::synthetic(Level::ERROR, "test", "a".to_owned(), &*clock);
+        let timeout = Duration::from_secs(10);
+
+        let locked = std::sync::Barrier::new(2);
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let buffer = collector.events.lock().unwrap();
+                locked.wait();
+                std::thread::sleep(Duration::from_millis(20));
+                drop(buffer);
+            });
+            locked.wait();
+            assert!(collector.try_push(event.clone(), timeout));
+        });
+
+        let buffer = collector.events.lock().unwrap();
+        assert!(!collector.try_push(event, Duration::from_millis(20)));
+        assert!(collector.try_events(Duration::from_millis(20)).is_none());
+        drop(buffer);
+        assert_eq!(collector.events().len(), 1);
     }
 }
@@ -1,33 +1,253 @@
 use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::time::Duration;
 
 use chrono::{DateTime, Local};
+use tracing::callsite::{Callsite, Identifier};
 use tracing::field::{Field, Visit};
-use tracing::{Event, Level, Metadata};
+use tracing::{Event, Metadata};
+
+use super::clock::Clock;
+use super::thread;
 
 #[derive(Debug, Clone)]
 pub struct CollectedEvent {
+    /// Position in the collector, assigned when the event is collected and
+    /// never reused, even after older events are evicted.
+    pub seq: u64,
+    /// The collector's revision when the event was added or last updated,
+    /// see [`events_changed_since`](super::EventCollector::events_changed_since).
+    pub revision: u64,
     pub target: String,
     pub level: tracing::Level,
     pub fields: BTreeMap<String, String>,
     pub time: DateTime<Local>,
+    /// Monotonic time since the collector's clock started.
+    pub elapsed: Duration,
+    pub callsite: Identifier,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    /// Number of identical consecutive events folded into this one when the
+    /// collector deduplicates, otherwise `1`.
+    pub count: u64,
+    /// Time of the last folded occurrence, equal to `time` if `count` is `1`.
+    pub last_time: DateTime<Local>,
+    pub last_elapsed: Duration,
+    /// The spans the event was emitted in, outermost first.
+    pub spans: Vec<CollectedSpan>,
+    /// The emitting thread, see [`thread::name`](super::thread::name).
+    pub thread: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectedSpan {
+    pub name: String,
+    pub fields: BTreeMap<String, String>,
+    /// Unique within the collector, unlike `tracing` span ids which are
+    /// reused. Equal to [`SpanRecord::seq`](super::SpanRecord::seq) if the
+    /// span was recorded.
+    pub id: u64,
+}
+
+impl std::fmt::Display for CollectedSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)?;
+        if !self.fields.is_empty() {
+            let fields = self
+                .fields
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>();
+            write!(f, "{{{}}}", fields.join(" "))?;
+        }
+        Ok(())
+    }
 }
 
 impl CollectedEvent {
-    pub fn new(event: &Event, meta: &Metadata) -> Self {
+    pub fn new(event: &Event, meta: &Metadata, clock: &dyn Clock) -> Self {
         let mut fields = BTreeMap::new();
         event.record(&mut FieldVisitor(&mut fields));
 
+        let time = clock.now();
+        let elapsed = clock.elapsed();
         CollectedEvent {
+            seq: 0,
+            revision: 0,
             level: meta.level().to_owned(),
-            time: Local::now(),
+            time,
+            elapsed,
             target: meta.target().to_owned(),
             fields,
+            callsite: meta.callsite(),
+            file: meta.file().map(str::to_owned),
+            line: meta.line(),
+            count: 1,
+            last_time: time,
+            last_elapsed: elapsed,
+            spans: Vec::new(),
+            thread: thread::current_id(),
+        }
+    }
+
+    /// The source location as `file:line`, if known.
+    pub fn location(&self) -> Option<String> {
+        let file = self.file.as_ref()?;
+        Some(match self.line {
+            Some(line) => format!("{file}:{line}"),
+            None => file.clone(),
+        })
+    }
+
+    /// Approximate heap memory owned by this event, in bytes.
+    pub fn heap_size(&self) -> usize {
+        self.target.capacity()
+            + self.file.as_ref().map_or(0, String::capacity)
+            + fields_size(&self.fields)
+            + self.spans.capacity() * std::mem::size_of::<CollectedSpan>()
+            + self
+                .spans
+                .iter()
+                .map(|span| span.name.capacity() + fields_size(&span.fields))
+                .sum::<usize>()
+    }
+
+    /// An event not emitted through `tracing`, e.g. console output.
+    pub fn synthetic(
+        level: tracing::Level,
+        target: &str,
+        message: String,
+        clock: &dyn Clock,
+    ) -> Self {
+        let time = clock.now();
+        let elapsed = clock.elapsed();
+        CollectedEvent {
+            seq: 0,
+            revision: 0,
+            target: target.to_owned(),
+            level,
+            fields: BTreeMap::from([("message".to_owned(), message)]),
+            time,
+            elapsed,
+            callsite: synthetic_callsite(),
+            file: None,
+            line: None,
+            count: 1,
+            last_time: time,
+            last_elapsed: elapsed,
+            spans: Vec::new(),
+            thread: thread::current_id(),
+        }
+    }
+
+    /// Converts a `log` record, keeping its key-value pairs as fields.
+    #[cfg(feature = "log")]
+    pub fn from_log(record: &log::Record, clock: &dyn Clock) -> Self {
+        let level = match record.level() {
+            log::Level::Error => tracing::Level::ERROR,
+            log::Level::Warn => tracing::Level::WARN,
+            log::Level::Info => tracing::Level::INFO,
+            log::Level::Debug => tracing::Level::DEBUG,
+            log::Level::Trace => tracing::Level::TRACE,
+        };
+        let mut event = Self::synthetic(level, record.target(), record.args().to_string(), clock);
+        event.file = record.file().map(str::to_owned);
+        event.line = record.line();
+        if let Some(module_path) = record.module_path() {
+            event
+                .fields
+                .insert("log.module_path".to_owned(), module_path.to_owned());
         }
+        let _ = record
+            .key_values()
+            .visit(&mut KeyValueVisitor(&mut event.fields));
+        event
+    }
+
+    /// A synthetic event standing in for `count` events dropped by a
+    /// [`Limit`](super::limit::Limit), starting with `first`.
+    pub fn suppressed(first: &CollectedEvent, count: u64) -> Self {
+        let mut event = CollectedEvent {
+            fields: BTreeMap::new(),
+            ..first.clone()
+        };
+        event.set_suppressed(count);
+        event
+    }
+
+    pub(crate) fn set_suppressed(&mut self, count: u64) {
+        self.fields
+            .insert("message".to_owned(), format!("{count} events suppressed"));
+        self.fields
+            .insert("suppressed".to_owned(), count.to_string());
+    }
+
+    /// Whether `other` comes from the same callsite with the same fields,
+    /// in spans with the same names and fields. Span ids are ignored, so
+    /// events in a span entered anew each frame still match.
+    pub fn same_as(&self, other: &CollectedEvent) -> bool {
+        self.callsite == other.callsite
+            && self.level == other.level
+            && self.target == other.target
+            && self.fields == other.fields
+            && self.spans.len() == other.spans.len()
+            && self
+                .spans
+                .iter()
+                .zip(&other.spans)
+                .all(|(span, other)| span.name == other.name && span.fields == other.fields)
+            && self.file == other.file
+            && self.line == other.line
+    }
+
+    /// Folds a repeated occurrence into this event.
+    pub(crate) fn fold(&mut self, other: &CollectedEvent) {
+        self.count += other.count;
+        self.last_time = other.last_time;
+        self.last_elapsed = other.last_elapsed;
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.fields.get("message").map(String::as_str)
+    }
+}
+
+/// Approximate heap memory owned by `fields`, in bytes.
+fn fields_size(fields: &BTreeMap<String, String>) -> usize {
+    fields
+        .iter()
+        .map(|(key, value)| {
+            std::mem::size_of::<(String, String)>() + key.capacity() + value.capacity()
+        })
+        .sum()
+}
+
+/// The callsite shared by events that did not come from a `tracing` macro.
+fn synthetic_callsite() -> Identifier {
+    let callsite = tracing::callsite! {
+        name: "synthetic",
+        kind: tracing::metadata::Kind::EVENT,
+        fields: message
+    };
+    callsite.metadata().callsite()
+}
+
+#[cfg(feature = "log")]
+struct KeyValueVisitor<'a>(&'a mut BTreeMap<String, String>);
+
+#[cfg(feature = "log")]
+impl<'a, 'kvs> log::kv::VisitSource<'kvs> for KeyValueVisitor<'a> {
+    fn visit_pair(
+        &mut self,
+        key: log::kv::Key<'kvs>,
+        value: log::kv::Value<'kvs>,
+    ) -> Result<(), log::kv::Error> {
+        self.0.insert(key.to_string(), value.to_string());
+        Ok(())
     }
 }
 
-struct FieldVisitor<'a>(&'a mut BTreeMap<String, String>);
+pub(crate) struct FieldVisitor<'a>(pub(crate) &'a mut BTreeMap<String, String>);
 
 impl<'a> Visit for FieldVisitor<'a> {
     fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
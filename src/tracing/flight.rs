@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tracing::Level;
+
+use super::event::CollectedEvent;
+use crate::export::EventFormat;
+
+/// Keeps recent events down to a verbose level in memory, without showing
+/// them, and writes the ones around each trigger event to a timestamped
+/// file in a directory. Files are written on a background thread.
+#[derive(Debug, Clone)]
+pub struct FlightRecorder {
+    dir: PathBuf,
+    level: Level,
+    trigger: Level,
+    before: Duration,
+    after: Duration,
+    state: Arc<Mutex<FlightState>>,
+}
+
+#[derive(Debug, Default)]
+struct FlightState {
+    events: VecDeque<CollectedEvent>,
+    /// End of the window of the file still receiving events.
+    dump_until: Option<Duration>,
+    writer: Option<Sender<Job>>,
+}
+
+/// Work for the thread writing the files.
+#[derive(Debug)]
+enum Job {
+    /// Starts a file with the events before and including a trigger.
+    Start {
+        path: PathBuf,
+        events: Vec<CollectedEvent>,
+    },
+    /// Appends to the file started last.
+    Append(CollectedEvent),
+}
+
+impl FlightRecorder {
+    /// Records events at any level and writes the 10 s before and 5 s after
+    /// each `ERROR` to files in `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            level: Level::TRACE,
+            trigger: Level::ERROR,
+            before: Duration::from_secs(10),
+            after: Duration::from_secs(5),
+            state: Arc::default(),
+        }
+    }
+
+    /// Records events at `level` or more severe.
+    pub fn level(self, level: Level) -> Self {
+        Self { level, ..self }
+    }
+
+    /// Writes a file for events at `trigger` or more severe.
+    pub fn trigger(self, trigger: Level) -> Self {
+        Self { trigger, ..self }
+    }
+
+    /// How far back the written files reach.
+    pub fn before(self, before: Duration) -> Self {
+        Self { before, ..self }
+    }
+
+    /// How long events keep being appended after a trigger. Triggers within
+    /// this time extend the same file.
+    pub fn after(self, after: Duration) -> Self {
+        Self { after, ..self }
+    }
+
+    pub(crate) fn level_limit(&self) -> Level {
+        self.level
+    }
+
+    pub(crate) fn record(&self, event: &CollectedEvent) {
+        if event.level <= self.level {
+            self.record_locked(&mut self.state.lock().unwrap(), event);
+        }
+    }
+
+    /// Like [`record`](Self::record), but skips `event` if the recorder stays
+    /// locked for `timeout`.
+    pub(crate) fn try_record(&self, event: &CollectedEvent, timeout: Duration) {
+        if event.level <= self.level {
+            if let Some(mut state) = super::thread::lock_within(&self.state, timeout) {
+                self.record_locked(&mut state, event);
+            }
+        }
+    }
+
+    fn record_locked(&self, state: &mut FlightState, event: &CollectedEvent) {
+        let now = event.elapsed;
+        let trigger = event.level <= self.trigger;
+
+        if state.dump_until.is_some_and(|until| now > until) {
+            state.dump_until = None;
+        }
+        let job = match &mut state.dump_until {
+            Some(until) => {
+                if trigger {
+                    *until = now + self.after;
+                }
+                Some(Job::Append(event.clone()))
+            }
+            None if trigger => {
+                state.dump_until = Some(now + self.after);
+                let start = now.saturating_sub(self.before);
+                let name = event
+                    .time
+                    .format("flight-%Y%m%d-%H%M%S%.3f.log")
+                    .to_string();
+                Some(Job::Start {
+                    path: self.dir.join(name),
+                    events: state
+                        .events
+                        .iter()
+                        .filter(|e| e.elapsed >= start)
+                        .chain([event])
+                        .cloned()
+                        .collect(),
+                })
+            }
+            None => None,
+        };
+        if let Some(job) = job {
+            // Fails only if the thread could not be started.
+            let _ = state.writer.get_or_insert_with(spawn_writer).send(job);
+        }
+
+        state.events.push_back(event.clone());
+        let start = now.saturating_sub(self.before);
+        while state.events.front().is_some_and(|e| e.elapsed < start) {
+            state.events.pop_front();
+        }
+    }
+}
+
+fn spawn_writer() -> Sender<Job> {
+    let (sender, receiver) = mpsc::channel();
+    let _ = thread::Builder::new()
+        .name("egui_tracing flight recorder".to_owned())
+        .spawn(move || write_files(receiver));
+    sender
+}
+
+/// Runs until every [`FlightRecorder`] clone is dropped. Files that fail to
+/// be written are given up until the next trigger.
+fn write_files(receiver: Receiver<Job>) {
+    let mut file = None;
+    while let Ok(job) = receiver.recv() {
+        let mut next = Some(job);
+        while let Some(job) = next {
+            let result = match job {
+                Job::Start { path, events } => {
+                    file = None;
+                    start_file(&path, &events).map(|started| file = Some(started))
+                }
+                Job::Append(event) => match &mut file {
+                    Some(file) => EventFormat::Text.write(file, [&event]),
+                    None => Ok(()),
+                },
+            };
+            if result.is_err() {
+                file = None;
+            }
+            next = receiver.try_recv().ok();
+        }
+        // Flush whenever the queue runs empty, so files are complete soon
+        // after a crash.
+        if file.as_mut().is_some_and(|file| file.flush().is_err()) {
+            file = None;
+        }
+    }
+}
+
+fn start_file(path: &Path, events: &[CollectedEvent]) -> io::Result<BufWriter<File>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut file = BufWriter::new(file);
+    EventFormat::Text.write(&mut file, events)?;
+    Ok(file)
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+enum LimitKind {
+    Rate { per_second: f64, burst: u32 },
+    Sample(u32),
+}
+
+/// Drops events of targets starting with a prefix, either with a token
+/// bucket or by keeping only every n-th event.
+#[derive(Debug, Clone)]
+pub struct Limit {
+    target: String,
+    kind: LimitKind,
+    per_callsite: bool,
+}
+
+impl Limit {
+    /// Allows on average `per_second` events, with bursts of up to `burst`.
+    pub fn rate(target: impl Into<String>, per_second: f64, burst: u32) -> Self {
+        Self {
+            target: target.into(),
+            kind: LimitKind::Rate { per_second, burst },
+            per_callsite: false,
+        }
+    }
+
+    /// Keeps one in every `n` events.
+    pub fn sample(target: impl Into<String>, n: u32) -> Self {
+        Self {
+            target: target.into(),
+            kind: LimitKind::Sample(n.max(1)),
+            per_callsite: false,
+        }
+    }
+
+    /// Tracks every callsite separately instead of sharing one limit
+    /// between all matching events.
+    pub fn per_callsite(self) -> Self {
+        Self {
+            per_callsite: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn matches(&self, target: &str) -> bool {
+        target.starts_with(&self.target)
+    }
+
+    pub(crate) fn is_per_callsite(&self) -> bool {
+        self.per_callsite
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct LimitState {
+    tokens: f64,
+    refilled: Duration,
+    seen: u64,
+    /// Sequence number of the row counting events suppressed since the last
+    /// admitted one.
+    pub(crate) suppressed_seq: Option<u64>,
+    pub(crate) suppressed: u64,
+}
+
+impl LimitState {
+    pub(crate) fn new(limit: &Limit, now: Duration) -> Self {
+        let tokens = match limit.kind {
+            LimitKind::Rate { burst, .. } => f64::from(burst),
+            LimitKind::Sample(_) => 0.0,
+        };
+        Self {
+            tokens,
+            refilled: now,
+            seen: 0,
+            suppressed_seq: None,
+            suppressed: 0,
+        }
+    }
+
+    /// Returns `true` if an event arriving at `now` should be kept.
+    pub(crate) fn admit(&mut self, limit: &Limit, now: Duration) -> bool {
+        let admitted = match limit.kind {
+            LimitKind::Rate { per_second, burst } => {
+                let refill = now.saturating_sub(self.refilled).as_secs_f64() * per_second;
+                self.tokens = (self.tokens + refill).min(f64::from(burst));
+                self.refilled = now;
+                if self.tokens >= 1.0 {
+                    self.tokens -= 1.0;
+                    true
+                } else {
+                    false
+                }
+            }
+            LimitKind::Sample(n) => {
+                self.seen += 1;
+                self.seen % u64::from(n) == 1 || n == 1
+            }
+        };
+        if admitted {
+            self.suppressed_seq = None;
+            self.suppressed = 0;
+        } else {
+            self.suppressed += 1;
+        }
+        admitted
+    }
+}
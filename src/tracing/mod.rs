@@ -1,5 +1,19 @@
+pub mod alert;
+pub mod clock;
 pub mod collector;
 pub mod event;
+pub mod flight;
+pub mod limit;
+pub mod panic;
+pub mod span;
+pub mod thread;
+pub mod timing;
 
+pub use alert::{Alert, AlertRule};
+pub use clock::{Clock, ManualClock, SystemClock};
 pub use collector::EventCollector;
-pub use event::CollectedEvent;
+pub use event::{CollectedEvent, CollectedSpan};
+pub use flight::FlightRecorder;
+pub use panic::PanicHook;
+pub use span::{SpanActivity, SpanPhase, SpanRecord};
+pub use timing::{DurationStats, SpanStats};
@@ -0,0 +1,94 @@
+use std::backtrace::Backtrace;
+use std::fs::File;
+use std::io::BufWriter;
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use tracing::Level;
+
+use super::collector::EventCollector;
+use super::event::CollectedEvent;
+use crate::export::EventFormat;
+
+/// How long the hook waits for a lock held by another thread. The panicking
+/// thread may hold it itself, so waiting forever could deadlock.
+const LOCK_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// A panic hook recording panics as `ERROR` events with target `panic`
+/// before calling the previous hook.
+pub struct PanicHook {
+    collector: EventCollector,
+    crash_file: Option<PathBuf>,
+}
+
+impl PanicHook {
+    pub fn new(collector: EventCollector) -> Self {
+        Self {
+            collector,
+            crash_file: None,
+        }
+    }
+
+    /// Also writes the collected events, including the panic, to `path` as
+    /// text.
+    pub fn crash_file(self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            crash_file: Some(path.into()),
+            ..self
+        }
+    }
+
+    pub fn install(self) {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            self.record(info);
+            previous(info);
+        }));
+    }
+
+    fn record(&self, info: &PanicHookInfo) {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        let thread = thread::current();
+
+        let mut event = CollectedEvent::synthetic(
+            Level::ERROR,
+            "panic",
+            format!("panicked: {message}"),
+            self.collector.clock(),
+        );
+        if let Some(location) = info.location() {
+            event.file = Some(location.file().to_owned());
+            event.line = Some(location.line());
+        }
+        event.fields.insert(
+            "thread".to_owned(),
+            thread.name().unwrap_or("<unnamed>").to_owned(),
+        );
+        event.fields.insert(
+            "backtrace".to_owned(),
+            Backtrace::force_capture().to_string(),
+        );
+        let pushed = self.collector.try_push(event.clone(), LOCK_TIMEOUT);
+
+        if let Some(path) = &self.crash_file {
+            let events = match self.collector.try_events(LOCK_TIMEOUT) {
+                Some(events) if pushed => events,
+                Some(mut events) => {
+                    events.push(event);
+                    events
+                }
+                None => vec![event],
+            };
+            // Nothing can be done about a failure while panicking.
+            let _ = File::create(path)
+                .and_then(|file| EventFormat::Text.write(&mut BufWriter::new(file), &events));
+        }
+    }
+}
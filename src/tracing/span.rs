@@ -0,0 +1,97 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+use tracing::Level;
+
+use super::timing::SpanStats;
+
+/// A span recorded by a collector with
+/// [`record_spans`](super::EventCollector::record_spans).
+#[derive(Debug, Clone)]
+pub struct SpanRecord {
+    /// Unique within the collector, unlike `tracing` span ids which are
+    /// reused, and equal to [`CollectedSpan::id`](super::CollectedSpan::id).
+    pub seq: u64,
+    pub parent: Option<u64>,
+    pub name: String,
+    pub target: String,
+    pub level: Level,
+    pub fields: BTreeMap<String, String>,
+    /// The thread that created the span.
+    pub thread: u64,
+    /// Monotonic time since the collector's clock started.
+    pub opened: Duration,
+    pub closed: Option<Duration>,
+    /// Time spent inside the span, set when it closes.
+    pub busy: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanPhase {
+    Enter,
+    Exit,
+}
+
+/// A span being entered or exited on a thread.
+#[derive(Debug, Clone, Copy)]
+pub struct SpanActivity {
+    pub span: u64,
+    pub phase: SpanPhase,
+    pub thread: u64,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SpanLog {
+    pub(crate) records: VecDeque<SpanRecord>,
+    pub(crate) activity: VecDeque<SpanActivity>,
+    pub(crate) stats: BTreeMap<String, SpanStats>,
+}
+
+impl SpanLog {
+    /// Adds `record`, whose sequence number must be higher than those of
+    /// all records added before.
+    pub(crate) fn open(&mut self, record: SpanRecord, max: Option<usize>) {
+        self.records.push_back(record);
+        if let Some(max) = max {
+            while self.records.len() > max {
+                self.records.pop_front();
+            }
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, seq: u64) -> Option<&mut SpanRecord> {
+        let index = self
+            .records
+            .binary_search_by_key(&seq, |record| record.seq)
+            .ok()?;
+        self.records.get_mut(index)
+    }
+
+    pub(crate) fn push_activity(&mut self, activity: SpanActivity, max: Option<usize>) {
+        self.activity.push_back(activity);
+        if let Some(max) = max {
+            while self.activity.len() > max {
+                self.activity.pop_front();
+            }
+        }
+    }
+
+    /// Adds the timings of a closed span to the stats for its name.
+    pub(crate) fn close(&mut self, name: &str, busy: Duration, idle: Duration) {
+        if !self.stats.contains_key(name) {
+            self.stats.insert(name.to_owned(), SpanStats::new(name));
+        }
+        if let Some(stats) = self.stats.get_mut(name) {
+            stats.busy.record(busy);
+            stats.idle.record(idle);
+        }
+    }
+
+    /// Removes the activity, the closed spans and their stats.
+    pub(crate) fn clear(&mut self) {
+        self.activity.clear();
+        self.records.retain(|record| record.closed.is_none());
+        self.stats.clear();
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+use std::time::{Duration, Instant};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static NAMES: Mutex<BTreeMap<u64, String>> = Mutex::new(BTreeMap::new());
+
+thread_local! {
+    static ID: u64 = {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        if let Some(name) = std::thread::current().name() {
+            NAMES.lock().unwrap().insert(id, name.to_owned());
+        }
+        id
+    };
+}
+
+/// A small number identifying the current thread, starting at `1` and never
+/// reused. `0` while the thread is being torn down.
+pub fn current_id() -> u64 {
+    ID.try_with(|id| *id).unwrap_or(0)
+}
+
+/// The name of the thread with `id`, if it was named.
+pub fn name(id: u64) -> Option<String> {
+    NAMES.lock().unwrap().get(&id).cloned()
+}
+
+/// Locks `mutex`, giving up after `timeout` instead of waiting forever, e.g.
+/// when the current thread already holds the lock while panicking.
+pub(crate) fn lock_within<T>(mutex: &Mutex<T>, timeout: Duration) -> Option<MutexGuard<'_, T>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match mutex.try_lock() {
+            Ok(guard) => return Some(guard),
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            Err(_) => return None,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::time::Duration;
+
+/// Histogram buckets per doubling of the duration.
+const BUCKETS_PER_OCTAVE: f64 = 8.0;
+/// Enough buckets for durations from 1 ns to about 18 minutes.
+const BUCKETS: usize = 320;
+
+/// Count, total, extremes and an approximate distribution of durations.
+#[derive(Debug, Clone)]
+pub struct DurationStats {
+    pub count: u64,
+    pub total: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    /// Counts per logarithmic bucket, see [`histogram`](Self::histogram).
+    buckets: Vec<u64>,
+}
+
+/// Timings of all closed spans with the same name, like the `time.busy` and
+/// `time.idle` reported by `tracing_subscriber`'s `FmtSpan::CLOSE`.
+#[derive(Debug, Clone)]
+pub struct SpanStats {
+    pub name: String,
+    /// Time spent inside the span, between enters and exits.
+    pub busy: DurationStats,
+    /// Time the span was open but not entered.
+    pub idle: DurationStats,
+}
+
+impl Default for DurationStats {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            total: Duration::ZERO,
+            min: Duration::MAX,
+            max: Duration::ZERO,
+            buckets: vec![0; BUCKETS],
+        }
+    }
+}
+
+impl DurationStats {
+    pub(crate) fn record(&mut self, duration: Duration) {
+        self.count += 1;
+        self.total += duration;
+        self.min = self.min.min(duration);
+        self.max = self.max.max(duration);
+        self.buckets[bucket(duration)] += 1;
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            count => Duration::from_nanos((self.total.as_nanos() / u128::from(count)) as u64),
+        }
+    }
+
+    /// The duration below which `p` percent of the spans fall, accurate to
+    /// the histogram's bucket width of about 9 %. Durations in the last
+    /// bucket, beyond about 18 minutes, are reported as `max`.
+    pub fn percentile(&self, p: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((p / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (index, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return bucket_end(index, self.max).clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
+
+    /// Non-empty range of buckets from `min` to `max` as
+    /// `(start, end, count)`, with logarithmically growing widths. The last
+    /// bucket ends at `max`.
+    pub fn histogram(&self) -> Vec<(Duration, Duration, u64)> {
+        if self.count == 0 {
+            return Vec::new();
+        }
+        (bucket(self.min)..=bucket(self.max))
+            .map(|index| {
+                (
+                    bucket_start(index),
+                    bucket_end(index, self.max),
+                    self.buckets[index],
+                )
+            })
+            .collect()
+    }
+}
+
+impl SpanStats {
+    pub(crate) fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            busy: DurationStats::default(),
+            idle: DurationStats::default(),
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.busy.count
+    }
+}
+
+fn bucket(duration: Duration) -> usize {
+    let nanos = duration.as_nanos().max(1) as f64;
+    ((nanos.log2() * BUCKETS_PER_OCTAVE) as usize).min(BUCKETS - 1)
+}
+
+fn bucket_start(index: usize) -> Duration {
+    Duration::from_nanos(2f64.powf(index as f64 / BUCKETS_PER_OCTAVE) as u64)
+}
+
+/// The end of bucket `index`, or `max` for the last bucket, which holds all
+/// longer durations.
+fn bucket_end(index: usize, max: Duration) -> Duration {
+    if index == BUCKETS - 1 {
+        max.max(bucket_start(index))
+    } else {
+        bucket_start(index + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(durations: impl IntoIterator<Item = Duration>) -> DurationStats {
+        let mut stats = DurationStats::default();
+        for duration in durations {
+            stats.record(duration);
+        }
+        stats
+    }
+
+    /// Whether `actual` is at most one bucket width above `expected`.
+    fn approx(actual: Duration, expected: Duration) -> bool {
+        let ratio = actual.as_secs_f64() / expected.as_secs_f64();
+        (1.0..2f64.powf(1.0 / BUCKETS_PER_OCTAVE)).contains(&ratio)
+    }
+
+    #[test]
+    fn empty_stats() {
+        let stats = DurationStats::default();
+        assert_eq!(stats.mean(), Duration::ZERO);
+        assert_eq!(stats.percentile(50.0), Duration::ZERO);
+        assert!(stats.histogram().is_empty());
+    }
+
+    #[test]
+    fn percentiles_of_uniform_durations() {
+        let stats = stats((1..=1000).map(Duration::from_millis));
+        for (p, expected) in [(50.0, 500), (90.0, 900), (99.0, 990)] {
+            let actual = stats.percentile(p);
+            assert!(
+                approx(actual, Duration::from_millis(expected)),
+                "p{p} = {actual:?}"
+            );
+        }
+        assert!(approx(stats.percentile(0.0), Duration::from_millis(1)));
+        assert_eq!(stats.percentile(100.0), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn percentiles_of_skewed_durations() {
+        let stats = stats(
+            std::iter::repeat_n(Duration::from_millis(1), 99).chain([Duration::from_secs(1)]),
+        );
+        assert!(approx(stats.percentile(50.0), Duration::from_millis(1)));
+        assert!(approx(stats.percentile(99.0), Duration::from_millis(1)));
+        assert_eq!(stats.percentile(99.5), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn percentile_rank_rounds_up() {
+        let stats = stats([10, 20, 30].map(Duration::from_millis));
+        assert!(approx(stats.percentile(33.0), Duration::from_millis(10)));
+        assert!(approx(stats.percentile(34.0), Duration::from_millis(20)));
+        assert!(approx(stats.percentile(50.0), Duration::from_millis(20)));
+        assert_eq!(stats.percentile(100.0), Duration::from_millis(30));
+    }
+
+    #[test]
+    fn percentiles_clamp_to_min_and_max() {
+        let stats = stats([Duration::from_micros(1500); 3]);
+        for p in [0.0, 50.0, 100.0] {
+            assert_eq!(stats.percentile(p), Duration::from_micros(1500));
+        }
+    }
+
+    #[test]
+    fn last_bucket_holds_longer_durations() {
+        let hour = Duration::from_secs(3600);
+        let stats = stats([Duration::from_secs(1), hour, 2 * hour]);
+        assert_eq!(bucket(hour), BUCKETS - 1);
+        assert_eq!(stats.percentile(50.0), 2 * hour);
+        let histogram = stats.histogram();
+        let &(start, end, count) = histogram.last().unwrap();
+        assert!(start < hour);
+        assert_eq!(end, 2 * hour);
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn histogram_covers_min_to_max() {
+        let stats = stats((1..=100).map(Duration::from_millis));
+        let histogram = stats.histogram();
+        assert!(histogram[0].0 <= stats.min && stats.min < histogram[0].1);
+        assert!(histogram.last().unwrap().1 > stats.max);
+        assert!(histogram.windows(2).all(|pair| pair[0].1 == pair[1].0));
+        assert_eq!(
+            histogram.iter().map(|(_, _, count)| count).sum::<u64>(),
+            100
+        );
+    }
+}
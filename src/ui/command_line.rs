@@ -0,0 +1,205 @@
+use egui::text::{CCursor, CCursorRange};
+use egui::text_edit::TextEditState;
+use egui::{Key, Modifiers, TextEdit, Ui};
+use tracing::Level;
+
+use super::state::LogsState;
+use crate::command::{self, CommandRegistry};
+use crate::tracing::EventCollector;
+
+/// Target of echoed commands and their output.
+const TARGET: &str = "console";
+const BUILTINS: [(&str, &str); 3] = [
+    ("help", "help - list commands"),
+    ("clear", "clear - remove all events"),
+    (
+        "level",
+        "level [<target>] <trace|debug|info|warn|error> | level reset",
+    ),
+];
+const LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "reset"];
+/// Number of commands kept in the history.
+const MAX_HISTORY: usize = 100;
+
+pub fn show(
+    ui: &mut Ui,
+    state: &mut LogsState,
+    registry: &CommandRegistry,
+    collector: &EventCollector,
+) {
+    let id = ui.id().with("command_line");
+    if ui.memory(|mem| mem.has_focus(id)) {
+        let mut history = None;
+        let mut complete = false;
+        ui.input_mut(|i| {
+            if i.consume_key(Modifiers::NONE, Key::ArrowUp) {
+                history = Some(-1);
+            }
+            if i.consume_key(Modifiers::NONE, Key::ArrowDown) {
+                history = Some(1);
+            }
+            complete = i.consume_key(Modifiers::NONE, Key::Tab);
+        });
+        if let Some(step) = history {
+            browse_history(state, step);
+            move_cursor_to_end(ui, id, &state.command);
+        }
+        if complete {
+            state.command = complete_line(&state.command, registry, collector);
+            move_cursor_to_end(ui, id, &state.command);
+        }
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(">");
+        let response = ui.add(
+            TextEdit::singleline(&mut state.command)
+                .id(id)
+                .lock_focus(true)
+                .hint_text("Command (Tab to complete, help for a list)")
+                .desired_width(f32::INFINITY),
+        );
+        if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+            let line = std::mem::take(&mut state.command);
+            if !line.trim().is_empty() {
+                if state.history.last() != Some(&line) {
+                    state.history.push(line.clone());
+                    let excess = state.history.len().saturating_sub(MAX_HISTORY);
+                    state.history.drain(..excess);
+                }
+                run(&line, state, registry, collector);
+                ui.ctx().request_repaint();
+            }
+            state.history_index = None;
+            response.request_focus();
+        }
+    });
+}
+
+fn run(line: &str, state: &mut LogsState, registry: &CommandRegistry, collector: &EventCollector) {
+    let words = command::parse(line);
+    let Some((name, args)) = words.split_first() else {
+        return;
+    };
+    collector.record(Level::INFO, TARGET, format!("> {line}"));
+    let result = match name.as_str() {
+        "help" => Ok(help(registry)),
+        "clear" => {
+            collector.clear();
+            state.clear_selection();
+            state.row_heights.clear();
+            Ok(String::new())
+        }
+        "level" => level(state, args),
+        name => match registry.get(name) {
+            Some(command) => command.run(args),
+            None => Err(format!("unknown command `{name}`, try `help`")),
+        },
+    };
+    match result {
+        Ok(output) if output.is_empty() => {}
+        Ok(output) => collector.record(Level::INFO, TARGET, output),
+        Err(error) => collector.record(Level::ERROR, TARGET, error),
+    }
+}
+
+fn help(registry: &CommandRegistry) -> String {
+    BUILTINS
+        .iter()
+        .map(|(_, help)| (*help).to_owned())
+        .chain(registry.commands().map(|command| {
+            if command.help_text().is_empty() {
+                command.name().to_owned()
+            } else {
+                format!("{} - {}", command.name(), command.help_text())
+            }
+        }))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn level(state: &mut LogsState, args: &[String]) -> Result<String, String> {
+    let parse = |level: &str| {
+        level
+            .parse::<Level>()
+            .map_err(|_| format!("unknown level `{level}`"))
+    };
+    match args {
+        [reset] if reset == "reset" => {
+            state.target_levels.clear();
+            state.level_filter.at_least(Level::TRACE);
+            Ok("showing all levels".to_owned())
+        }
+        [level] => {
+            let level = parse(level)?;
+            state.level_filter.at_least(level);
+            Ok(format!("showing {level} and above"))
+        }
+        [target, level] => {
+            let level = parse(level)?;
+            state.target_levels.retain(|(t, _)| t != target);
+            state.target_levels.push((target.clone(), level));
+            Ok(format!("showing {level} and above for {target}"))
+        }
+        _ => Err(BUILTINS[2].1.to_owned()),
+    }
+}
+
+fn browse_history(state: &mut LogsState, step: isize) {
+    if state.history.is_empty() {
+        return;
+    }
+    let last = state.history.len() - 1;
+    state.history_index = match (state.history_index, step < 0) {
+        (None, true) => Some(last),
+        (None, false) => None,
+        (Some(index), true) => Some(index.saturating_sub(1)),
+        (Some(index), false) if index < last => Some(index + 1),
+        (Some(_), false) => None,
+    };
+    state.command = state
+        .history_index
+        .map(|index| state.history[index].clone())
+        .unwrap_or_default();
+}
+
+/// Completes the last word of `line`, listing the candidates if ambiguous.
+fn complete_line(line: &str, registry: &CommandRegistry, collector: &EventCollector) -> String {
+    let start = line.rfind(char::is_whitespace).map_or(0, |index| index + 1);
+    let (head, prefix) = line.split_at(start);
+    let mut words = command::parse(head);
+    words.push(prefix.to_owned());
+
+    let candidates = match words.as_slice() {
+        [_] => BUILTINS
+            .iter()
+            .map(|(name, _)| (*name).to_owned())
+            .chain(registry.commands().map(|command| command.name().to_owned()))
+            .collect(),
+        [name, ..] if name == "level" => LEVELS.iter().map(|level| (*level).to_owned()).collect(),
+        [name, args @ ..] => registry
+            .get(name)
+            .map(|command| command.complete(args))
+            .unwrap_or_default(),
+        [] => Vec::new(),
+    };
+    let (common, matches) = command::complete(prefix, candidates.iter().map(String::as_str));
+    match matches.as_slice() {
+        [] => line.to_owned(),
+        [_] => format!("{head}{common} "),
+        _ => {
+            if common == prefix {
+                collector.record(Level::INFO, TARGET, matches.join("  "));
+            }
+            format!("{head}{common}")
+        }
+    }
+}
+
+fn move_cursor_to_end(ui: &Ui, id: egui::Id, text: &str) {
+    if let Some(mut state) = TextEditState::load(ui.ctx(), id) {
+        let end = CCursor::new(text.chars().count());
+        state.set_ccursor_range(Some(CCursorRange::one(end)));
+        state.store(ui.ctx(), id);
+    }
+}
@@ -0,0 +1,69 @@
+use egui::{Align, Frame, Layout, RichText, Stroke, Ui};
+
+use crate::string::Ellipse;
+use crate::tracing::Alert;
+use crate::ui::style::LogsStyle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BannerAction {
+    Show,
+    Dismiss,
+}
+
+#[derive(Default)]
+pub struct AlertBanner<'a> {
+    alert: Option<&'a Alert>,
+    style: Option<&'a LogsStyle>,
+}
+
+impl<'a> AlertBanner<'a> {
+    pub fn alert(mut self, v: &'a Alert) -> Self {
+        self.alert = Some(v);
+        self
+    }
+
+    pub fn style(mut self, v: &'a LogsStyle) -> Self {
+        self.style = Some(v);
+        self
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Option<BannerAction> {
+        let alert = self.alert.unwrap();
+        let style = self.style.unwrap();
+        let color = style.level_color(alert.event.level);
+        let mut action = None;
+        Frame::none()
+            .stroke(Stroke::new(1.0, color))
+            .fill(color.linear_multiply(0.1))
+            .inner_margin(4.0)
+            .rounding(2.0)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(format!("⚠ {}", alert.rule))
+                            .color(color)
+                            .strong(),
+                    );
+                    if alert.count > 1 {
+                        ui.label(format!("({} events)", alert.count));
+                    }
+                    let message = alert.event.message().unwrap_or_default().to_owned();
+                    ui.colored_label(style.text_color, message.truncate_graphemes(120))
+                        .on_hover_text(&message);
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        if ui.small_button("✖").on_hover_text("Dismiss").clicked() {
+                            action = Some(BannerAction::Dismiss);
+                        }
+                        if ui
+                            .small_button("Show")
+                            .on_hover_text("Select the Event")
+                            .clicked()
+                        {
+                            action = Some(BannerAction::Show);
+                        }
+                    });
+                });
+            });
+        action
+    }
+}
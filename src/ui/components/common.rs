@@ -2,6 +2,8 @@ use std::ops::RangeInclusive;
 
 use egui::{Ui, Vec2};
 
+pub type Children<'a> = Box<dyn FnMut(&mut Ui) + 'a>;
+
 #[derive(Default, Debug)]
 pub struct CommonProps {
     enabled: Option<bool>,
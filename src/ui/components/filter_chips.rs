@@ -0,0 +1,117 @@
+use egui::Ui;
+
+use crate::string::Ellipse;
+use crate::time::TimeFormat;
+use crate::ui::state::LogsState;
+
+#[derive(Default)]
+pub struct FilterChips<'a> {
+    state: Option<&'a mut LogsState>,
+}
+
+impl<'a> FilterChips<'a> {
+    pub fn state(mut self, v: &'a mut LogsState) -> Self {
+        self.state = Some(v);
+        self
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let state = self.state.unwrap();
+        if state.target_filter.only.is_empty()
+            && state.field_filters.is_empty()
+            && state.time_window.is_none()
+            && state.pattern.is_none()
+            && state.location.is_none()
+            && state.span.is_none()
+            && state.follow.is_none()
+            && state.target_levels.is_empty()
+        {
+            return;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Filters:");
+
+            let mut remove_only = None;
+            for (i, glob) in state.target_filter.only.iter().enumerate() {
+                if chip(ui, &format!("target = {}", glob.glob())) {
+                    remove_only = Some(i);
+                }
+            }
+            if let Some(i) = remove_only {
+                state.target_filter.only.remove(i);
+            }
+
+            let mut remove_field = None;
+            for (i, (key, value)) in state.field_filters.iter().enumerate() {
+                if chip(ui, &format!("{key} = {value}")) {
+                    remove_field = Some(i);
+                }
+            }
+            if let Some(i) = remove_field {
+                state.field_filters.remove(i);
+            }
+
+            if let Some(window) = state.time_window {
+                let center = TimeFormat::Local.format_time(&window.center);
+                let label = format!("{center} ±{} s", window.seconds);
+                if chip(ui, &label) {
+                    state.time_window = None;
+                }
+            }
+
+            if let Some(pattern) = &state.pattern {
+                if chip(ui, &format!("pattern = {pattern}")) {
+                    state.pattern = None;
+                }
+            }
+
+            let mut remove_level = None;
+            for (i, (target, level)) in state.target_levels.iter().enumerate() {
+                if chip(ui, &format!("{target} ≥ {level}")) {
+                    remove_level = Some(i);
+                }
+            }
+            if let Some(i) = remove_level {
+                state.target_levels.remove(i);
+            }
+
+            if let Some(location) = &state.location {
+                if chip(ui, &format!("location = {location}")) {
+                    state.location = None;
+                }
+            }
+
+            if let Some((_, name)) = &state.span {
+                if chip(ui, &format!("span = {name}")) {
+                    state.span = None;
+                }
+            }
+
+            if let Some(correlation) = &state.follow {
+                if chip(ui, &format!("follow {}", correlation.label())) {
+                    state.follow = None;
+                }
+            }
+
+            if ui.small_button("Clear All").clicked() {
+                state.target_filter.only.clear();
+                state.field_filters.clear();
+                state.time_window = None;
+                state.pattern = None;
+                state.location = None;
+                state.span = None;
+                state.follow = None;
+                state.target_levels.clear();
+            }
+        });
+        ui.separator();
+    }
+}
+
+/// Shows a removable filter, returning `true` if it should be removed.
+fn chip(ui: &mut Ui, label: &str) -> bool {
+    ui.small_button(format!("{} ✖", label.to_owned().truncate_graphemes(32)))
+        .on_hover_text(format!("Remove filter {label}"))
+        .clicked()
+}
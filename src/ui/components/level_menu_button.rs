@@ -1,13 +1,12 @@
 use egui::{RichText, Ui};
 
-use super::common::{set_common_props, CommonProps};
-use crate::ui::color::{DEBUG_COLOR, ERROR_COLOR, INFO_COLOR, TRACE_COLOR, WARN_COLOR};
 use crate::ui::state::LevelFilter;
+use crate::ui::style::LogsStyle;
 
 #[derive(Default)]
 pub struct LevelMenuButton<'a> {
     state: Option<&'a mut LevelFilter>,
-    common_props: Option<CommonProps>,
+    style: Option<&'a LogsStyle>,
 }
 
 impl<'a> LevelMenuButton<'a> {
@@ -16,35 +15,35 @@ impl<'a> LevelMenuButton<'a> {
         self
     }
 
-    pub fn common_props(mut self, v: CommonProps) -> Self {
-        self.common_props = Some(v);
+    pub fn style(mut self, v: &'a LogsStyle) -> Self {
+        self.style = Some(v);
         self
     }
 
     pub fn show(mut self, ui: &mut Ui) {
         let state = self.state.as_mut().unwrap();
+        let style = self.style.unwrap();
         ui.menu_button("Level", |ui| {
-            set_common_props(ui, &self.common_props);
             ui.label("Level Filter");
             ui.add(egui::Checkbox::new(
                 &mut state.trace,
-                RichText::new("TRACE").color(TRACE_COLOR),
+                RichText::new("TRACE").color(style.trace_color),
             ));
             ui.add(egui::Checkbox::new(
                 &mut state.debug,
-                RichText::new("DEBUG").color(DEBUG_COLOR),
+                RichText::new("DEBUG").color(style.debug_color),
             ));
             ui.add(egui::Checkbox::new(
                 &mut state.info,
-                RichText::new("INFO").color(INFO_COLOR),
+                RichText::new("INFO").color(style.info_color),
             ));
             ui.add(egui::Checkbox::new(
                 &mut state.warn,
-                RichText::new("WARN").color(WARN_COLOR),
+                RichText::new("WARN").color(style.warn_color),
             ));
             ui.add(egui::Checkbox::new(
                 &mut state.error,
-                RichText::new("ERROR").color(ERROR_COLOR),
+                RichText::new("ERROR").color(style.error_color),
             ));
         });
     }
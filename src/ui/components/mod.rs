@@ -1,8 +1,11 @@
+pub mod alert_banner;
 pub mod common;
 pub mod constants;
+pub mod filter_chips;
 pub mod level_menu_button;
 pub mod table;
 pub mod table_cell;
 pub mod table_header;
 pub mod target_menu_button;
 pub mod target_menu_item;
+pub mod time_menu_button;
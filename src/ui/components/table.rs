@@ -1,22 +1,76 @@
+use std::collections::{HashMap, HashSet};
 use std::slice::Iter;
 
-use egui::{Color32, Response, Ui};
+use egui::{Align, Id, Layout, Modifiers, Rect, Response, Rounding, Sense, Shape, Ui};
 
+use super::common::Children;
 use super::constants::SEPARATOR_SPACING;
+use crate::ui::style::LogsStyle;
+
+type RowFn<'a, T> = Box<dyn FnMut(&mut Ui, usize, &T) + 'a>;
+type RowMenuFn<'a> = Box<dyn FnMut(&mut Ui, usize) + 'a>;
+
+/// Measured heights of variable-height rows, keyed by a stable row id.
+#[derive(Debug, Default)]
+pub struct RowHeights {
+    width: f32,
+    heights: HashMap<u64, f32>,
+}
+
+impl RowHeights {
+    pub fn clear(&mut self) {
+        self.heights.clear();
+    }
+
+    fn get(&self, id: u64, default: f32) -> f32 {
+        self.heights.get(&id).copied().unwrap_or(default)
+    }
+
+    /// Forgets rows that are no longer shown once they outnumber the shown
+    /// ones.
+    fn retain(&mut self, ids: &[u64]) {
+        if self.heights.len() > ids.len() {
+            let shown: HashSet<u64> = ids.iter().copied().collect();
+            self.heights.retain(|id, _| shown.contains(id));
+        }
+    }
+
+    fn top(&self, ids: &[u64], row: usize, default: f32) -> f32 {
+        ids[..row].iter().map(|id| self.get(*id, default)).sum()
+    }
+}
 
 pub struct Table<'a, T> {
     row_height: Option<f32>,
+    row_ids: Option<&'a [u64]>,
+    row_heights: Option<&'a mut RowHeights>,
+    style: Option<&'a LogsStyle>,
+    scroll_to_row: Option<usize>,
     on_clear: Option<Box<dyn FnMut() + 'a>>,
-    header: Option<Box<dyn FnMut(&mut Ui) + 'a>>,
-    row: Option<Box<dyn FnMut(&mut Ui, &T) + 'a>>,
+    on_row_clicked: Option<Box<dyn FnMut(usize, Modifiers) + 'a>>,
+    is_selected: Option<Box<dyn Fn(usize) -> bool + 'a>>,
+    is_highlighted: Option<Box<dyn Fn(usize) -> bool + 'a>>,
+    row_context_menu: Option<RowMenuFn<'a>>,
+    header: Option<Children<'a>>,
+    toolbar: Option<Children<'a>>,
+    row: Option<RowFn<'a, T>>,
 }
 
 impl<'a, T> Default for Table<'a, T> {
     fn default() -> Self {
         Self {
             row_height: None,
+            row_ids: None,
+            row_heights: None,
+            style: None,
+            scroll_to_row: None,
             on_clear: None,
+            on_row_clicked: None,
+            is_selected: None,
+            is_highlighted: None,
+            row_context_menu: None,
             header: None,
+            toolbar: None,
             row: None,
         }
     }
@@ -28,75 +82,257 @@ impl<'a, T> Table<'a, T> {
         self
     }
 
+    /// Identifies each row across frames, so its interaction state and
+    /// measured height stay with it when rows are added or removed.
+    pub fn row_ids(mut self, v: &'a [u64]) -> Self {
+        self.row_ids = Some(v);
+        self
+    }
+
+    /// Lays out rows with their measured heights instead of `row_height`.
+    /// Requires [`row_ids`](Self::row_ids).
+    pub fn variable_row_heights(mut self, v: &'a mut RowHeights) -> Self {
+        self.row_heights = Some(v);
+        self
+    }
+
+    pub fn style(mut self, v: &'a LogsStyle) -> Self {
+        self.style = Some(v);
+        self
+    }
+
+    pub fn scroll_to_row(mut self, v: Option<usize>) -> Self {
+        self.scroll_to_row = v;
+        self
+    }
+
     pub fn on_clear(mut self, v: impl FnMut() + 'a) -> Self {
         self.on_clear = Some(Box::new(v));
         self
     }
 
+    pub fn on_row_clicked(mut self, v: impl FnMut(usize, Modifiers) + 'a) -> Self {
+        self.on_row_clicked = Some(Box::new(v));
+        self
+    }
+
+    pub fn selected(mut self, v: impl Fn(usize) -> bool + 'a) -> Self {
+        self.is_selected = Some(Box::new(v));
+        self
+    }
+
+    /// Rows drawn with the highlight color unless selected.
+    pub fn highlighted(mut self, v: impl Fn(usize) -> bool + 'a) -> Self {
+        self.is_highlighted = Some(Box::new(v));
+        self
+    }
+
+    pub fn row_context_menu(mut self, v: impl FnMut(&mut Ui, usize) + 'a) -> Self {
+        self.row_context_menu = Some(Box::new(v));
+        self
+    }
+
     pub fn header(mut self, v: impl FnMut(&mut Ui) + 'a) -> Self {
         self.header = Some(Box::new(v));
         self
     }
 
-    pub fn row(mut self, v: impl FnMut(&mut Ui, &T) + 'a) -> Self {
+    pub fn toolbar(mut self, v: impl FnMut(&mut Ui) + 'a) -> Self {
+        self.toolbar = Some(Box::new(v));
+        self
+    }
+
+    pub fn row(mut self, v: impl FnMut(&mut Ui, usize, &T) + 'a) -> Self {
         self.row = Some(Box::new(v));
         self
     }
 
     pub fn show(self, ui: &mut Ui, values: Iter<&T>) -> Response {
+        let style = self.style.unwrap();
+        let mut on_row_clicked = self.on_row_clicked;
+        let is_selected = self.is_selected;
+        let is_highlighted = self.is_highlighted;
+        let mut row_context_menu = self.row_context_menu;
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                ui.style_mut().visuals.override_text_color = Some(Color32::WHITE);
+                ui.style_mut().visuals.override_text_color = Some(style.header_text_color);
 
                 ui.horizontal(|ui| {
                     (self.header.unwrap())(ui);
                 });
 
-                ui.add_space(ui.available_width() - 130.0);
-
-                if ui
-                    .button("To Bottom")
-                    .on_hover_text("Scroll to Bottom")
-                    .clicked()
-                {
-                    ui.scroll_to_rect(
-                        egui::Rect {
-                            min: egui::Pos2 { x: 0.0, y: 0.0 },
-                            max: egui::Pos2 {
-                                x: f32::MAX,
-                                y: f32::MAX,
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.button("Clear").on_hover_text("Clear Events").clicked() {
+                        (self.on_clear.unwrap())();
+                    }
+
+                    ui.separator();
+
+                    if ui
+                        .button("To Bottom")
+                        .on_hover_text("Scroll to Bottom")
+                        .clicked()
+                    {
+                        ui.scroll_to_rect(
+                            egui::Rect {
+                                min: egui::Pos2 { x: 0.0, y: 0.0 },
+                                max: egui::Pos2 {
+                                    x: f32::MAX,
+                                    y: f32::MAX,
+                                },
                             },
-                        },
-                        Some(egui::Align::Max),
-                    );
+                            Some(egui::Align::Max),
+                        );
+                    }
+
+                    if let Some(mut toolbar) = self.toolbar {
+                        ui.separator();
+                        toolbar(ui);
+                    }
+                });
+            });
+
+            ui.separator();
+
+            let row_height = self.row_height.unwrap() + SEPARATOR_SPACING;
+            let row_height_with_spacing = row_height + ui.spacing().item_spacing.y;
+            let row_ids = self.row_ids;
+            let mut row_heights = self.row_heights.map(|heights| (heights, row_ids.unwrap()));
+
+            let mut scroll_area = egui::ScrollArea::vertical()
+                .id_source("table")
+                .auto_shrink([false, false])
+                .stick_to_bottom(true);
+
+            if let Some(row) = self.scroll_to_row {
+                let scroll_id = ui.make_persistent_id(Id::new("table"));
+                let offset = egui::scroll_area::State::load(ui.ctx(), scroll_id)
+                    .map_or(0.0, |state| state.offset.y);
+                let viewport_height = ui.available_height();
+                let (top, height) = match &row_heights {
+                    Some((heights, ids)) => (
+                        heights.top(ids, row, row_height_with_spacing),
+                        heights.get(ids[row], row_height_with_spacing),
+                    ),
+                    None => (
+                        row as f32 * row_height_with_spacing,
+                        row_height_with_spacing,
+                    ),
+                };
+                let bottom = top + height;
+                if top < offset {
+                    scroll_area = scroll_area.vertical_scroll_offset(top);
+                } else if bottom > offset + viewport_height {
+                    scroll_area = scroll_area.vertical_scroll_offset(bottom - viewport_height);
+                }
+            }
+
+            let mut row = self.row.unwrap();
+            let mut show_row = |ui: &mut Ui, index: usize, value: &T| {
+                let background = ui.painter().add(Shape::Noop);
+                let rect = ui
+                    .horizontal(|ui| {
+                        row(ui, index, value);
+                    })
+                    .response
+                    .rect;
+                let id = row_ids.map_or(index as u64, |ids| ids[index]);
+                let response = ui.interact(rect, ui.id().with(("row", id)), Sense::click());
+
+                if response.clicked() {
+                    if let Some(on_row_clicked) = on_row_clicked.as_mut() {
+                        on_row_clicked(index, ui.input(|i| i.modifiers));
+                    }
+                }
+
+                if let Some(row_context_menu) = row_context_menu.as_mut() {
+                    response
+                        .clone()
+                        .context_menu(|ui| row_context_menu(ui, index));
                 }
 
+                let selected = is_selected.as_ref().is_some_and(|f| f(index));
+                let fill = if selected {
+                    Some(style.selection_color)
+                } else if is_highlighted.as_ref().is_some_and(|f| f(index)) {
+                    Some(style.highlight_color)
+                } else if style.striped && index % 2 == 1 {
+                    Some(style.stripe_color)
+                } else {
+                    None
+                };
+                if let Some(fill) = fill {
+                    let rect =
+                        Rect::from_x_y_ranges(ui.max_rect().x_range(), response.rect.y_range())
+                            .expand2(egui::vec2(
+                                0.0,
+                                ui.spacing().item_spacing.y + SEPARATOR_SPACING / 2.0,
+                            ));
+                    ui.painter()
+                        .set(background, Shape::rect_filled(rect, Rounding::none(), fill));
+                }
                 ui.separator();
+            };
 
-                if ui.button("Clear").on_hover_text("Clear Events").clicked() {
-                    (self.on_clear.unwrap())();
+            match row_heights.as_mut() {
+                None => {
+                    scroll_area.show_rows(ui, row_height, values.len(), |ui, range| {
+                        ui.style_mut().override_text_style = Some(style.text_style.clone());
+                        let start = range.start;
+                        for (index, value) in values.skip(start).take(range.len()).enumerate() {
+                            show_row(ui, start + index, value);
+                        }
+                    });
                 }
-            });
+                Some((heights, ids)) => {
+                    let values = values.as_slice();
+                    heights.retain(ids);
+                    scroll_area.show_viewport(ui, |ui, viewport| {
+                        ui.style_mut().override_text_style = Some(style.text_style.clone());
 
-            ui.separator();
+                        let width = ui.available_width();
+                        if (heights.width - width).abs() > 0.5 {
+                            heights.width = width;
+                            heights.clear();
+                        }
 
-            let mut row = self.row.unwrap();
-            egui::ScrollArea::vertical()
-                .auto_shrink([false, false])
-                .stick_to_bottom(true)
-                .show_rows(
-                    ui,
-                    self.row_height.unwrap() + SEPARATOR_SPACING,
-                    values.len(),
-                    |ui, range| {
-                        for value in values.skip(range.start).take(range.len()) {
-                            ui.horizontal(|ui| {
-                                row(ui, value);
-                            });
-                            ui.separator();
+                        let mut first = (values.len(), 0.0);
+                        let mut total = 0.0;
+                        for (index, id) in ids.iter().enumerate() {
+                            let height = heights.get(*id, row_height_with_spacing);
+                            if first.0 == values.len() && total + height > viewport.min.y {
+                                first = (index, total);
+                            }
+                            total += height;
                         }
-                    },
-                )
+                        ui.set_height((total - ui.spacing().item_spacing.y).max(0.0));
+
+                        let content_top = ui.max_rect().top();
+                        let rect = Rect::from_x_y_ranges(
+                            ui.max_rect().x_range(),
+                            content_top + first.1..=content_top + total,
+                        );
+                        ui.allocate_ui_at_rect(rect, |ui| {
+                            ui.skip_ahead_auto_ids(first.0);
+                            for index in first.0..values.len() {
+                                let top = ui.cursor().top();
+                                if top - content_top > viewport.max.y {
+                                    break;
+                                }
+                                show_row(ui, index, values[index]);
+                                let measured = ui.cursor().top() - top;
+                                let previous = heights
+                                    .heights
+                                    .insert(ids[index], measured)
+                                    .unwrap_or(row_height_with_spacing);
+                                if (previous - measured).abs() > 0.5 {
+                                    ui.ctx().request_repaint();
+                                }
+                            }
+                        });
+                    });
+                }
+            }
         })
         .response
     }
@@ -15,7 +15,7 @@ impl<'a> TargetMenuButton<'a> {
         self
     }
 
-    pub fn show(mut self, ui: &mut Ui) {
+    pub fn show(self, ui: &mut Ui) {
         let state = self.state.unwrap();
         ui.menu_button("Target", |ui| {
             ui.label("Target Filter");
@@ -42,9 +42,22 @@ impl<'a> TargetMenuButton<'a> {
                     .on_clicked(|| {
                         state.targets.remove(i);
                     })
-                    .target(&target)
+                    .target(target)
                     .show(ui);
             }
+
+            if !state.only.is_empty() {
+                ui.separator();
+                ui.label("Only Showing");
+                for (i, target) in state.only.clone().iter().enumerate() {
+                    TargetMenuItem::default()
+                        .on_clicked(|| {
+                            state.only.remove(i);
+                        })
+                        .target(target)
+                        .show(ui);
+                }
+            }
         });
     }
 }
@@ -0,0 +1,46 @@
+use egui::Ui;
+
+use crate::time::TimeFormat;
+
+const PRESETS: [TimeFormat; 6] = [
+    TimeFormat::Local,
+    TimeFormat::Utc,
+    TimeFormat::Rfc3339,
+    TimeFormat::SinceStart,
+    TimeFormat::SinceSelected,
+    TimeFormat::DeltaPrevious,
+];
+
+const DEFAULT_CUSTOM_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+#[derive(Default)]
+pub struct TimeMenuButton<'a> {
+    state: Option<&'a mut TimeFormat>,
+}
+
+impl<'a> TimeMenuButton<'a> {
+    pub fn state(mut self, v: &'a mut TimeFormat) -> Self {
+        self.state = Some(v);
+        self
+    }
+
+    pub fn show(self, ui: &mut Ui) {
+        let state = self.state.unwrap();
+        ui.menu_button("Time", |ui| {
+            ui.label("Time Format");
+            for preset in PRESETS {
+                let label = preset.label();
+                ui.radio_value(state, preset, label);
+            }
+
+            let is_custom = matches!(state, TimeFormat::Custom(_));
+            if ui.radio(is_custom, "Custom").clicked() && !is_custom {
+                *state = TimeFormat::Custom(DEFAULT_CUSTOM_FORMAT.to_owned());
+            }
+            if let TimeFormat::Custom(format) = state {
+                ui.text_edit_singleline(format)
+                    .on_hover_text("strftime format, example: %H:%M:%S%.6f");
+            }
+        });
+    }
+}
@@ -0,0 +1,164 @@
+use egui::{
+    pos2, vec2, Area, Context, CursorIcon, Frame, Id, Key, Modifiers, Order, Sense, Slider,
+};
+use serde::{Deserialize, Serialize};
+
+use super::style::LogsStyle;
+use super::Logs;
+use crate::command::CommandRegistry;
+use crate::tracing::EventCollector;
+
+/// A drop-down overlay hosting [`Logs`] that slides over the [`Context`]
+/// when the toggle key is pressed.
+pub struct Console {
+    collector: EventCollector,
+    id: Id,
+    toggle_key: Key,
+    height: f32,
+    opacity: f32,
+    style: Option<LogsStyle>,
+    jump_to: Option<u64>,
+    commands: Option<CommandRegistry>,
+}
+
+/// Remembered across frames and, with egui's `persistence` feature, across
+/// restarts.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct ConsoleState {
+    open: bool,
+    /// Fraction of the screen height.
+    height: f32,
+    opacity: f32,
+}
+
+impl Console {
+    #[must_use]
+    pub fn new(collector: EventCollector) -> Self {
+        Self {
+            collector,
+            id: Id::new("egui_tracing_console"),
+            toggle_key: Key::F12,
+            height: 0.4,
+            opacity: 0.95,
+            style: None,
+            jump_to: None,
+            commands: None,
+        }
+    }
+
+    /// Distinguishes several consoles in the same [`Context`].
+    #[must_use]
+    pub fn id_source(mut self, v: impl std::hash::Hash) -> Self {
+        self.id = Id::new(v);
+        self
+    }
+
+    /// Defaults to `F12`.
+    #[must_use]
+    pub fn toggle_key(mut self, v: Key) -> Self {
+        self.toggle_key = v;
+        self
+    }
+
+    /// Initial height as a fraction of the screen height.
+    #[must_use]
+    pub fn height(mut self, v: f32) -> Self {
+        self.height = v.clamp(0.1, 1.0);
+        self
+    }
+
+    /// Initial background opacity between `0.0` and `1.0`.
+    #[must_use]
+    pub fn opacity(mut self, v: f32) -> Self {
+        self.opacity = v.clamp(0.0, 1.0);
+        self
+    }
+
+    #[must_use]
+    pub fn style(mut self, v: LogsStyle) -> Self {
+        self.style = Some(v);
+        self
+    }
+
+    /// Opens the console and jumps to the event with sequence number `v`.
+    #[must_use]
+    pub fn jump_to(mut self, v: u64) -> Self {
+        self.jump_to = Some(v);
+        self
+    }
+
+    /// Shows a command line below the table, see [`Logs::commands`].
+    #[must_use]
+    pub fn commands(mut self, v: CommandRegistry) -> Self {
+        self.commands = Some(v);
+        self
+    }
+
+    /// Shows the console if it is open, returning whether it is.
+    pub fn show(self, ctx: &Context) -> bool {
+        let mut state = ctx
+            .data_mut(|data| data.get_persisted::<ConsoleState>(self.id))
+            .unwrap_or(ConsoleState {
+                open: false,
+                height: self.height,
+                opacity: self.opacity,
+            });
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, self.toggle_key)) {
+            state.open = !state.open;
+        }
+        if self.jump_to.is_some() {
+            state.open = true;
+        }
+
+        let shown = ctx.animate_bool_with_time(self.id.with("animation"), state.open, 0.15);
+        if state.open || shown > 0.0 {
+            let screen = ctx.screen_rect();
+            let height = screen.height() * state.height;
+            let top = screen.top() - height * (1.0 - shown);
+            Area::new(self.id)
+                .fixed_pos(pos2(screen.left(), top))
+                .order(Order::Foreground)
+                .show(ctx, |ui| {
+                    let fill = ui.visuals().panel_fill.linear_multiply(state.opacity);
+                    let handle_height = 6.0;
+                    Frame::none().fill(fill).inner_margin(6.0).show(ui, |ui| {
+                        let size = vec2(screen.width() - 12.0, height - handle_height - 12.0);
+                        ui.set_min_size(size);
+                        ui.set_max_size(size);
+                        ui.horizontal(|ui| {
+                            ui.label("Opacity");
+                            ui.add(Slider::new(&mut state.opacity, 0.2..=1.0).show_value(false));
+                            ui.weak(format!("{:?} to toggle", self.toggle_key));
+                            if ui.small_button("✖").on_hover_text("Close").clicked() {
+                                state.open = false;
+                            }
+                        });
+                        let mut logs = Logs::new(self.collector.clone());
+                        if let Some(style) = self.style.clone() {
+                            logs = logs.style(style);
+                        }
+                        if let Some(seq) = self.jump_to {
+                            logs = logs.jump_to(seq);
+                        }
+                        if let Some(commands) = self.commands {
+                            logs = logs.commands(commands);
+                        }
+                        ui.add(logs);
+                    });
+
+                    let (rect, response) =
+                        ui.allocate_exact_size(vec2(screen.width(), handle_height), Sense::drag());
+                    let response = response.on_hover_cursor(CursorIcon::ResizeVertical);
+                    ui.painter()
+                        .rect_filled(rect, 0.0, ui.style().interact(&response).bg_fill);
+                    if response.dragged() {
+                        let height = height + response.drag_delta().y;
+                        state.height = (height / screen.height()).clamp(0.1, 1.0);
+                    }
+                });
+        }
+
+        ctx.data_mut(|data| data.insert_persisted(self.id, state));
+        state.open
+    }
+}
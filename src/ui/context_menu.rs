@@ -0,0 +1,105 @@
+use egui::Ui;
+use globset::Glob;
+use tracing::Level;
+
+use super::state::{LogsState, TimeWindow};
+use crate::string::Ellipse;
+use crate::tracing::CollectedEvent;
+
+const TIME_WINDOWS: [i64; 4] = [1, 5, 30, 60];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowAction {
+    OnlyTarget,
+    HideTarget,
+    LevelAndAbove,
+    FieldEquals(String, String),
+    Around(i64),
+    Copy,
+}
+
+impl RowAction {
+    /// Applies a filter action to `state`. [`RowAction::Copy`] is left to
+    /// the caller.
+    pub fn apply(&self, state: &mut LogsState, event: &CollectedEvent) {
+        match self {
+            Self::OnlyTarget => {
+                if let Ok(glob) = Glob::new(&globset::escape(&event.target)) {
+                    state.target_filter.only = vec![glob];
+                }
+            }
+            Self::HideTarget => {
+                if let Ok(glob) = Glob::new(&globset::escape(&event.target)) {
+                    state.target_filter.targets.push(glob);
+                }
+            }
+            Self::LevelAndAbove => state.level_filter.at_least(event.level),
+            Self::FieldEquals(key, value) => {
+                state.field_filters.retain(|(k, _)| k != key);
+                state.field_filters.push((key.clone(), value.clone()));
+            }
+            Self::Around(seconds) => {
+                state.time_window = Some(TimeWindow {
+                    center: event.time,
+                    seconds: *seconds,
+                });
+            }
+            Self::Copy => {}
+        }
+    }
+}
+
+pub fn show(ui: &mut Ui, event: &CollectedEvent) -> Option<RowAction> {
+    let mut action = None;
+
+    let target = event.target.truncate_graphemes(24);
+    if ui.button(format!("Show only {target}")).clicked() {
+        action = Some(RowAction::OnlyTarget);
+    }
+    if ui.button(format!("Hide {target}")).clicked() {
+        action = Some(RowAction::HideTarget);
+    }
+    if event.level != Level::ERROR
+        && ui
+            .button(format!("Only {} and above", event.level))
+            .clicked()
+    {
+        action = Some(RowAction::LevelAndAbove);
+    }
+
+    let fields = event
+        .fields
+        .iter()
+        .filter(|(key, _)| key.as_str() != "message")
+        .collect::<Vec<_>>();
+    if !fields.is_empty() {
+        ui.menu_button("Filter by field", |ui| {
+            for (key, value) in fields {
+                let label = format!("{key} = {}", value.truncate_graphemes(32));
+                if ui.button(label).clicked() {
+                    action = Some(RowAction::FieldEquals(key.clone(), value.clone()));
+                }
+            }
+        });
+    }
+
+    ui.menu_button("Show events around", |ui| {
+        for seconds in TIME_WINDOWS {
+            if ui.button(format!("±{seconds} s")).clicked() {
+                action = Some(RowAction::Around(seconds));
+            }
+        }
+    });
+
+    ui.separator();
+
+    if ui.button("Copy").clicked() {
+        action = Some(RowAction::Copy);
+    }
+
+    if action.is_some() {
+        ui.close_menu();
+    }
+
+    action
+}
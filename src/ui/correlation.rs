@@ -0,0 +1,71 @@
+use crate::tracing::CollectedEvent;
+
+/// What related events have in common with a selected one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Correlation {
+    /// Emitted inside the span, directly or in a child span.
+    Span { id: u64, name: String },
+    /// Having `key` set to `value` on the event or one of its spans.
+    Field { key: String, value: String },
+}
+
+impl Correlation {
+    /// Correlates by the field `key` of `event`, or by its innermost span if
+    /// `key` is `None`.
+    pub fn of(event: &CollectedEvent, key: Option<&str>) -> Option<Self> {
+        match key {
+            Some(key) => field(event, key).map(|value| Self::Field {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            }),
+            None => event.spans.last().map(|span| Self::Span {
+                id: span.id,
+                name: span.name.clone(),
+            }),
+        }
+    }
+
+    pub fn matches(&self, event: &CollectedEvent) -> bool {
+        match self {
+            Self::Span { id, .. } => event.spans.iter().any(|span| span.id == *id),
+            Self::Field { key, value } => field(event, key) == Some(value.as_str()),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Span { name, .. } => format!("span {name}"),
+            Self::Field { key, value } => format!("{key} = {value}"),
+        }
+    }
+}
+
+/// The value of `key` on the event itself or else on its innermost span
+/// having it.
+fn field<'a>(event: &'a CollectedEvent, key: &str) -> Option<&'a str> {
+    event
+        .fields
+        .get(key)
+        .or_else(|| {
+            event
+                .spans
+                .iter()
+                .rev()
+                .find_map(|span| span.fields.get(key))
+        })
+        .map(String::as_str)
+}
+
+/// Field names offered for correlating by, from `event` and its spans.
+pub fn keys(event: &CollectedEvent) -> Vec<String> {
+    let mut keys = event
+        .fields
+        .keys()
+        .chain(event.spans.iter().flat_map(|span| span.fields.keys()))
+        .filter(|key| key.as_str() != "message")
+        .cloned()
+        .collect::<Vec<_>>();
+    keys.sort_unstable();
+    keys.dedup();
+    keys
+}
@@ -0,0 +1,90 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use super::state::LogsState;
+use crate::tracing::CollectedEvent;
+
+/// The combination of all filters in [`LogsState`], prepared for matching
+/// many events.
+pub struct Filter<'a> {
+    state: &'a LogsState,
+    hidden: GlobSet,
+    only: GlobSet,
+    search: String,
+    span: bool,
+}
+
+impl<'a> Filter<'a> {
+    // TODO: cache the globsets
+    pub fn new(state: &'a LogsState) -> Self {
+        Self {
+            state,
+            hidden: build_glob_set(&state.target_filter.targets),
+            only: build_glob_set(&state.target_filter.only),
+            search: state.search.to_lowercase(),
+            span: true,
+        }
+    }
+
+    /// Lets events outside of the span selected in [`LogsState::span`] match.
+    pub fn ignore_span(mut self) -> Self {
+        self.span = false;
+        self
+    }
+
+    pub fn matches(&self, event: &CollectedEvent) -> bool {
+        self.level_matches(event)
+            && !self.hidden.is_match(&event.target)
+            && (self.only.is_empty() || self.only.is_match(&event.target))
+            && self
+                .state
+                .field_filters
+                .iter()
+                .all(|(key, value)| event.fields.get(key) == Some(value))
+            && self
+                .state
+                .location
+                .as_ref()
+                .is_none_or(|location| event.location().as_ref() == Some(location))
+            && self
+                .state
+                .follow
+                .as_ref()
+                .is_none_or(|correlation| correlation.matches(event))
+            && (!self.span
+                || self
+                    .state
+                    .span
+                    .as_ref()
+                    .is_none_or(|(seq, _)| event.spans.iter().any(|span| span.id == *seq)))
+            && self
+                .state
+                .time_window
+                .as_ref()
+                .is_none_or(|window| window.contains(event.time))
+            && (self.search.is_empty()
+                || event
+                    .message()
+                    .is_some_and(|message| message.to_lowercase().contains(&self.search)))
+    }
+
+    fn level_matches(&self, event: &CollectedEvent) -> bool {
+        let target_level = self
+            .state
+            .target_levels
+            .iter()
+            .filter(|(target, _)| event.target.starts_with(target.as_str()))
+            .max_by_key(|(target, _)| target.len());
+        match target_level {
+            Some((_, level)) => event.level <= *level,
+            None => self.state.level_filter.get(event.level),
+        }
+    }
+}
+
+fn build_glob_set(globs: &[Glob]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(glob.clone());
+    }
+    builder.build().unwrap()
+}
@@ -0,0 +1,83 @@
+use egui::{Event, Key, Modifiers, Ui};
+use tracing::Level;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    By(isize),
+    First,
+    Last,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Move(Movement),
+    Extend(Movement),
+    SelectAll,
+    ClearSelection,
+    FocusSearch,
+    Copy,
+    TogglePause,
+    ToggleLevel(Level),
+}
+
+const LEVEL_KEYS: [(Key, Level); 5] = [
+    (Key::Num1, Level::TRACE),
+    (Key::Num2, Level::DEBUG),
+    (Key::Num3, Level::INFO),
+    (Key::Num4, Level::WARN),
+    (Key::Num5, Level::ERROR),
+];
+
+/// Consumes the keyboard shortcuts of the `Logs` widget for this frame.
+pub fn actions(ui: &Ui, page: isize) -> Vec<Action> {
+    ui.input_mut(|i| {
+        let mut actions = Vec::new();
+
+        let movements = [
+            (Key::ArrowUp, Movement::By(-1)),
+            (Key::ArrowDown, Movement::By(1)),
+            (Key::PageUp, Movement::By(-page)),
+            (Key::PageDown, Movement::By(page)),
+            (Key::Home, Movement::First),
+            (Key::End, Movement::Last),
+        ];
+        for (key, movement) in movements {
+            if i.consume_key(Modifiers::NONE, key) {
+                actions.push(Action::Move(movement));
+            }
+            if i.consume_key(Modifiers::SHIFT, key) {
+                actions.push(Action::Extend(movement));
+            }
+        }
+
+        if i.consume_key(Modifiers::NONE, Key::Escape) {
+            actions.push(Action::ClearSelection);
+        }
+
+        if i.consume_key(Modifiers::NONE, Key::Space) {
+            actions.push(Action::TogglePause);
+        }
+
+        for (key, level) in LEVEL_KEYS {
+            if i.consume_key(Modifiers::NONE, key) {
+                actions.push(Action::ToggleLevel(level));
+            }
+        }
+
+        if i.consume_key(Modifiers::COMMAND, Key::A) {
+            actions.push(Action::SelectAll);
+        }
+
+        if i.consume_key(Modifiers::COMMAND, Key::F) {
+            actions.push(Action::FocusSearch);
+        }
+
+        if i.events.iter().any(|event| matches!(event, Event::Copy))
+            || i.consume_key(Modifiers::COMMAND, Key::C)
+        {
+            actions.push(Action::Copy);
+        }
+
+        actions
+    })
+}
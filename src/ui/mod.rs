@@ -1,76 +1,370 @@
-mod color;
+mod command_line;
 mod components;
+mod console;
+mod context_menu;
+mod correlation;
+mod filter;
+mod keyboard;
+mod patterns;
+mod span_tree;
 mod state;
+mod stats;
+mod status;
+mod style;
+mod timings;
+mod toasts;
 
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
-use egui::{Color32, Label, Response, TextStyle, Widget};
-use globset::GlobSetBuilder;
+use chrono::Local;
+use egui::{
+    Context, Frame, Id, Label, Modifiers, Response, RichText, TextEdit, TopBottomPanel, Ui, Widget,
+};
+use tracing::Level;
 
-use self::color::ToColor32;
+use self::components::alert_banner::{AlertBanner, BannerAction};
 use self::components::common::CommonProps;
 use self::components::constants;
+use self::components::filter_chips::FilterChips;
 use self::components::level_menu_button::LevelMenuButton;
 use self::components::table::Table;
 use self::components::table_cell::TableCell;
 use self::components::table_header::TableHeader;
 use self::components::target_menu_button::TargetMenuButton;
-use self::state::LogsState;
+use self::components::time_menu_button::TimeMenuButton;
+pub use self::console::Console;
+use self::context_menu::RowAction;
+use self::correlation::Correlation;
+use self::filter::Filter;
+use self::keyboard::Action;
+use self::state::{LogsState, View};
+pub use self::status::LogStatus;
+pub use self::style::LogsStyle;
+pub use self::toasts::Toasts;
+use crate::command::CommandRegistry;
+use crate::export::{self, EventFormat};
 use crate::string::Ellipse;
-use crate::time::DateTimeFormatExt;
+use crate::time::{TimeContext, TimeFormat};
 use crate::tracing::collector::EventCollector;
 use crate::tracing::CollectedEvent;
 
+/// Alert banners shown at once, newest first.
+const MAX_BANNERS: usize = 3;
+/// Target of events reporting exported files.
+const EXPORT_TARGET: &str = "egui_tracing::export";
+
 pub struct Logs {
     collector: EventCollector,
+    style: Option<LogsStyle>,
+    jump_to: Option<u64>,
+    commands: Option<CommandRegistry>,
+    export_dir: Option<PathBuf>,
 }
 
 impl Logs {
     #[must_use]
     pub const fn new(collector: EventCollector) -> Self {
-        Self { collector }
+        Self {
+            collector,
+            style: None,
+            jump_to: None,
+            commands: None,
+            export_dir: None,
+        }
+    }
+
+    #[must_use]
+    pub fn style(mut self, v: LogsStyle) -> Self {
+        self.style = Some(v);
+        self
+    }
+
+    /// Selects the event with sequence number `v` and scrolls to it, e.g.
+    /// after a [`Toasts`] click.
+    #[must_use]
+    pub fn jump_to(mut self, v: u64) -> Self {
+        self.jump_to = Some(v);
+        self
+    }
+
+    /// Shows a command line below the table with the built-in commands and
+    /// those in `v`.
+    #[must_use]
+    pub fn commands(mut self, v: CommandRegistry) -> Self {
+        self.commands = Some(v);
+        self
+    }
+
+    /// Directory the Export menu writes files to. Defaults to the working
+    /// directory.
+    #[must_use]
+    pub fn export_dir(mut self, v: impl Into<PathBuf>) -> Self {
+        self.export_dir = Some(v.into());
+        self
+    }
+
+    /// Writes the events this widget shows with its current filters,
+    /// returning how many were written. `id` is the [`Ui::id`] of the `Ui`
+    /// the widget is added to.
+    pub fn export(
+        &self,
+        ctx: &Context,
+        id: Id,
+        format: EventFormat,
+        writer: &mut impl Write,
+    ) -> io::Result<usize> {
+        let state = load_state(ctx, id);
+        let mut state = state.lock().unwrap();
+        let events = self.collector.events();
+        let filtered_events = filter_events(&state, &events);
+        let filtered_events = filter_pattern(&mut state, filtered_events);
+        format.write(writer, filtered_events.iter().copied())?;
+        Ok(filtered_events.len())
+    }
+
+    /// Creates a new timestamped file in the export directory and passes it
+    /// to `write`. Files of exports within the same second get a counter.
+    fn export_file(
+        &self,
+        extension: &str,
+        write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+    ) -> io::Result<PathBuf> {
+        let dir = self.export_dir.clone().unwrap_or_default();
+        if !dir.as_os_str().is_empty() {
+            fs::create_dir_all(&dir)?;
+        }
+        let stem = format!("logs-{}", Local::now().format("%Y%m%d-%H%M%S"));
+        let mut n = 0;
+        let (path, file) = loop {
+            let path = match n {
+                0 => dir.join(format!("{stem}.{extension}")),
+                n => dir.join(format!("{stem}-{n}.{extension}")),
+            };
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => break (path, file),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => n += 1,
+                Err(err) => return Err(err),
+            }
+        };
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        writer.flush()?;
+        Ok(path)
+    }
+}
+
+fn load_state(ctx: &Context, id: Id) -> Arc<Mutex<LogsState>> {
+    ctx.memory_mut(|mem| {
+        mem.data
+            .get_temp_mut_or_insert_with(id, || Arc::new(Mutex::new(LogsState::default())))
+            .clone()
+    })
+}
+
+/// Events passing all filters except the pattern filter.
+fn filter_events<'a>(state: &LogsState, events: &'a [CollectedEvent]) -> Vec<&'a CollectedEvent> {
+    matching_events(state, &Filter::new(state), events)
+}
+
+fn matching_events<'a>(
+    state: &LogsState,
+    filter: &Filter,
+    events: &'a [CollectedEvent],
+) -> Vec<&'a CollectedEvent> {
+    let paused = state.paused.unwrap_or(u64::MAX);
+    events
+        .iter()
+        .filter(|event| event.seq < paused && filter.matches(event))
+        .collect()
+}
+
+fn filter_pattern<'a>(
+    state: &mut LogsState,
+    events: Vec<&'a CollectedEvent>,
+) -> Vec<&'a CollectedEvent> {
+    match &state.pattern {
+        Some(pattern) => events
+            .into_iter()
+            .filter(|event| *state.templates.get(event) == **pattern)
+            .collect(),
+        None => events,
     }
 }
 
 impl Widget for Logs {
     fn ui(self, ui: &mut egui::Ui) -> Response {
-        let state = ui.memory_mut(|mem| {
-            let state_mem_id = ui.id();
-            mem.data
-                .get_temp_mut_or_insert_with(state_mem_id, || {
-                    Arc::new(Mutex::new(LogsState::default()))
-                })
-                .clone()
-        });
+        let state = load_state(ui.ctx(), ui.id());
         let mut state = state.lock().unwrap();
+        let state = &mut *state;
+        let search_id = ui.id().with("search");
 
-        // TODO: cache the globset
-        let glob = {
-            let mut glob = GlobSetBuilder::new();
-            for target in state.target_filter.targets.clone() {
-                glob.add(target);
-            }
-            glob.build().unwrap()
+        let style = self
+            .style
+            .clone()
+            .unwrap_or_else(|| LogsStyle::from_visuals(ui.visuals()));
+        let row_height = ui.text_style_height(&style.text_style) + ui.spacing().item_spacing.y;
+
+        let rect = ui.available_rect_before_wrap();
+        if ui.input(|i| i.pointer.any_pressed()) {
+            state.focused = ui
+                .input(|i| i.pointer.interact_pos())
+                .is_some_and(|pos| rect.contains(pos));
+        }
+        let actions = if state.focused && !ui.ctx().wants_keyboard_input() {
+            let page = (rect.height() / (row_height + constants::SEPARATOR_SPACING)) as isize;
+            keyboard::actions(ui, page.max(1))
+        } else {
+            Vec::new()
         };
 
         let events = self.collector.events();
-        let filtered_events = events
+        let next_seq = events.last().map_or(0, |event| event.seq + 1);
+        for action in &actions {
+            match *action {
+                Action::TogglePause => {
+                    state.paused = match state.paused {
+                        Some(_) => None,
+                        None => Some(next_seq),
+                    };
+                }
+                Action::ToggleLevel(level) => state.level_filter.toggle(level),
+                Action::FocusSearch => ui.memory_mut(|mem| mem.request_focus(search_id)),
+                _ => {}
+            }
+        }
+
+        let mut jump_to = self.jump_to;
+        let alerts = self.collector.alerts();
+        // Newest first, the rest are summarized.
+        for alert in alerts.iter().rev().take(MAX_BANNERS) {
+            match AlertBanner::default().alert(alert).style(&style).show(ui) {
+                Some(BannerAction::Show) => {
+                    self.collector.dismiss_alert(alert);
+                    jump_to = Some(alert.event.seq);
+                }
+                Some(BannerAction::Dismiss) => self.collector.dismiss_alert(alert),
+                None => {}
+            }
+        }
+        if alerts.len() > MAX_BANNERS
+            && ui
+                .link(format!("Dismiss all {} alerts", alerts.len()))
+                .clicked()
+        {
+            self.collector.dismiss_alerts();
+        }
+        if let Some(seq) = jump_to {
+            if state.paused.is_some_and(|paused| seq >= paused) {
+                state.paused = None;
+            }
+            state.selection = [seq].into();
+            state.cursor = Some(seq);
+            state.anchor = Some(seq);
+            state.view = View::Events;
+        }
+
+        FilterChips::default().state(state).show(ui);
+
+        if let Some(registry) = &self.commands {
+            TopBottomPanel::bottom(ui.id().with("commands"))
+                .frame(Frame::none())
+                .show_inside(ui, |ui| {
+                    command_line::show(ui, state, registry, &self.collector);
+                });
+        }
+
+        let filtered_events = filter_events(state, &events);
+
+        if let Some(first) = events.first() {
+            state.templates.retain_from(first.seq);
+        }
+        match state.view {
+            View::Events => {}
+            View::Patterns => {
+                return patterns::show(ui, state, &filtered_events, &style, &self.collector)
+            }
+            View::Stats => return stats::show(ui, state, &events, &style, &self.collector),
+            View::Timings => return timings::show(ui, state, &style, &self.collector),
+            View::Spans => {
+                // The tree shows every span, so it ignores the span filter.
+                let filter = Filter::new(state).ignore_span();
+                let events = matching_events(state, &filter, &events);
+                return span_tree::show(ui, state, &events, &style, &self.collector);
+            }
+        }
+        let filtered_events = filter_pattern(state, filtered_events);
+        let seqs = filtered_events
             .iter()
-            .filter(|event| state.level_filter.get(event.level) && !glob.is_match(&event.target))
+            .map(|event| event.seq)
             .collect::<Vec<_>>();
 
-        let row_height = constants::SEPARATOR_SPACING
-            + ui.style().text_styles.get(&TextStyle::Small).unwrap().size;
+        let mut scroll_to_row = jump_to.and_then(|seq| seqs.iter().position(|s| *s == seq));
+        for action in &actions {
+            match *action {
+                Action::Move(movement) => {
+                    scroll_to_row = state.move_cursor(&seqs, movement, false);
+                }
+                Action::Extend(movement) => {
+                    scroll_to_row = state.move_cursor(&seqs, movement, true);
+                }
+                Action::SelectAll => state.selection = seqs.iter().copied().collect(),
+                Action::ClearSelection => state.clear_selection(),
+                Action::Copy => copy_selection(ui, state, &filtered_events),
+                _ => {}
+            }
+        }
 
-        Table::default()
+        let selection = state.selection.clone();
+        let mut clicked_row = None;
+        let mut copy_format = None;
+        let mut export_format = None;
+        let mut export_trace = false;
+        let mut row_action = None;
+        let mut cleared = false;
+        let wrap = state.wrap;
+        let show_spans = filtered_events.iter().any(|event| !event.spans.is_empty());
+        let time_format = state.time_format.clone();
+        let cursor_event = state
+            .cursor
+            .and_then(|cursor| events.binary_search_by_key(&cursor, |event| event.seq).ok())
+            .map(|index| &events[index]);
+        let time_context = TimeContext {
+            selected: cursor_event.map(|event| event.elapsed),
+            previous: None,
+        };
+        let correlation =
+            cursor_event.and_then(|event| Correlation::of(event, state.correlate_by.as_deref()));
+        let correlation_keys = cursor_event.map(correlation::keys).unwrap_or_default();
+        let mut table = Table::default()
+            .style(&style)
+            .scroll_to_row(scroll_to_row)
+            .row_ids(&seqs)
+            .selected(|row| selection.contains(&seqs[row]))
+            .highlighted(|row| {
+                correlation
+                    .as_ref()
+                    .is_some_and(|correlation| correlation.matches(filtered_events[row]))
+            })
+            .on_row_clicked(|row, modifiers| clicked_row = Some((row, modifiers)))
+            .row_context_menu(|ui, row| {
+                if let Some(action) = context_menu::show(ui, filtered_events[row]) {
+                    row_action = Some((row, action));
+                }
+            })
             .on_clear(|| {
                 self.collector.clear();
+                cleared = true;
             })
             .header(|ui| {
                 TableHeader::default()
                     .common_props(CommonProps::default().min_width(100.0))
                     .children(|ui| {
-                        ui.label("Time");
+                        TimeMenuButton::default()
+                            .state(&mut state.time_format)
+                            .show(ui)
                     })
                     .show(ui);
                 TableHeader::default()
@@ -78,6 +372,7 @@ impl Widget for Logs {
                     .children(|ui| {
                         LevelMenuButton::default()
                             .state(&mut state.level_filter)
+                            .style(&style)
                             .show(ui)
                     })
                     .show(ui);
@@ -89,6 +384,14 @@ impl Widget for Logs {
                             .show(ui)
                     })
                     .show(ui);
+                if show_spans {
+                    TableHeader::default()
+                        .common_props(CommonProps::default().min_width(120.0))
+                        .children(|ui| {
+                            ui.label("Span");
+                        })
+                        .show(ui);
+                }
                 TableHeader::default()
                     .common_props(CommonProps::default().min_width(120.0))
                     .children(|ui| {
@@ -96,39 +399,291 @@ impl Widget for Logs {
                     })
                     .show(ui);
             })
+            .toolbar(|ui| {
+                view_switch(ui, &mut state.view, self.collector.records_spans());
+                ui.add_enabled_ui(!selection.is_empty(), |ui| {
+                    ui.menu_button("Copy", |ui| {
+                        for format in EventFormat::ALL {
+                            if ui.button(format.label()).clicked() {
+                                copy_format = Some(format);
+                                ui.close_menu();
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text("Copy Selected Events (Ctrl+C)");
+                });
+                ui.menu_button("Export", |ui| {
+                    for format in EventFormat::ALL {
+                        if ui.button(format.label()).clicked() {
+                            export_format = Some(format);
+                            ui.close_menu();
+                        }
+                    }
+                    ui.separator();
+                    if ui
+                        .button("Chrome Trace")
+                        .on_hover_text("Spans and Events for Perfetto or chrome://tracing")
+                        .clicked()
+                    {
+                        export_trace = true;
+                        ui.close_menu();
+                    }
+                })
+                .response
+                .on_hover_text("Save Filtered Events to a File");
+                correlate_menu(ui, &mut state.correlate_by, &correlation_keys);
+                let following = state.follow.is_some();
+                let response = ui
+                    .add_enabled(
+                        following || correlation.is_some(),
+                        egui::SelectableLabel::new(following, "Follow"),
+                    )
+                    .on_hover_text(match &correlation {
+                        Some(correlation) => {
+                            format!("Show Only Events with {}", correlation.label())
+                        }
+                        None => "Show Only Events Correlated with the Selected One".to_owned(),
+                    });
+                if response.clicked() {
+                    state.follow = if following { None } else { correlation.clone() };
+                }
+                ui.toggle_value(&mut state.wrap, "Wrap")
+                    .on_hover_text("Show Multi-Line Messages in Full");
+                let paused = state.paused.is_some();
+                if ui
+                    .selectable_label(paused, "Pause")
+                    .on_hover_text("Pause (Space)")
+                    .clicked()
+                {
+                    state.paused = if paused { None } else { Some(next_seq) };
+                }
+                ui.add(
+                    TextEdit::singleline(&mut state.search)
+                        .id(search_id)
+                        .hint_text("Search (Ctrl+F)")
+                        .desired_width(150.0),
+                );
+            })
             .row_height(row_height)
-            .row(|ui, event: &CollectedEvent| {
+            .row(|ui, row, event: &CollectedEvent| {
+                let context = TimeContext {
+                    previous: row.checked_sub(1).map(|row| filtered_events[row].elapsed),
+                    ..time_context
+                };
                 TableCell::default()
                     .common_props(CommonProps::default().min_width(100.0))
                     .children(|ui| {
-                        ui.colored_label(Color32::GRAY, event.time.format_short())
-                            .on_hover_text(event.time.format_detailed());
+                        ui.colored_label(
+                            style.weak_text_color,
+                            time_format.format(event, &context),
+                        )
+                        .on_hover_text(TimeFormat::Rfc3339.format(event, &context));
                     })
                     .show(ui);
                 TableCell::default()
                     .common_props(CommonProps::default().min_width(80.0))
                     .children(|ui| {
-                        ui.colored_label(event.level.to_color32(), event.level.as_str());
+                        ui.colored_label(style.level_color(event.level), event.level.as_str());
                     })
                     .show(ui);
                 TableCell::default()
                     .common_props(CommonProps::default().min_width(120.0))
                     .children(|ui| {
-                        ui.colored_label(Color32::GRAY, event.target.truncate_graphemes(18))
-                            .on_hover_text(&event.target);
+                        ui.colored_label(
+                            style.weak_text_color,
+                            event.target.truncate_graphemes(18),
+                        )
+                        .on_hover_text(&event.target);
                     })
                     .show(ui);
+                if show_spans {
+                    TableCell::default()
+                        .common_props(CommonProps::default().min_width(120.0))
+                        .children(|ui| {
+                            if let Some(span) = event.spans.last() {
+                                let scope = event
+                                    .spans
+                                    .iter()
+                                    .map(ToString::to_string)
+                                    .collect::<Vec<_>>()
+                                    .join("\n");
+                                ui.colored_label(
+                                    style.weak_text_color,
+                                    span.to_string().truncate_graphemes(18),
+                                )
+                                .on_hover_text(scope);
+                            }
+                        })
+                        .show(ui);
+                }
                 TableCell::default()
                     .common_props(CommonProps::default().min_width(120.0))
                     .children(|ui| {
-                        let message = event.fields.get("message").unwrap();
+                        let message = event.message().unwrap_or_default();
 
-                        ui.style_mut().visuals.override_text_color = Some(Color32::WHITE);
-                        ui.add(Label::new(message).wrap(false))
-                            .on_hover_text(message);
+                        if event.count > 1 {
+                            ui.label(
+                                RichText::new(format!("×{}", event.count))
+                                    .small()
+                                    .strong()
+                                    .color(style.level_color(event.level)),
+                            )
+                            .on_hover_text(format!(
+                                "Repeated {} times\nFirst: {}\nLast: {}",
+                                event.count,
+                                TimeFormat::Rfc3339.format_time(&event.time),
+                                TimeFormat::Rfc3339.format_time(&event.last_time),
+                            ));
+                        }
+                        ui.style_mut().visuals.override_text_color = Some(style.text_color);
+                        if wrap {
+                            ui.add(Label::new(message).wrap(true));
+                        } else {
+                            let mut lines = message.lines();
+                            let first_line = lines.next().unwrap_or_default();
+                            let label = if lines.next().is_some() {
+                                format!("{first_line} …")
+                            } else {
+                                first_line.to_owned()
+                            };
+                            ui.add(Label::new(label).wrap(false)).on_hover_text(message);
+                        }
                     })
                     .show(ui);
-            })
-            .show(ui, filtered_events.iter())
+            });
+        if wrap {
+            table = table.variable_row_heights(&mut state.row_heights);
+        }
+        let response = table.show(ui, filtered_events.iter());
+
+        if cleared {
+            state.clear_selection();
+            state.row_heights.clear();
+        }
+
+        if let Some((row, modifiers)) = clicked_row {
+            state.click_row(&seqs, row, modifiers);
+            state.focused = true;
+        }
+
+        if let Some((row, action)) = row_action {
+            if action == RowAction::Copy {
+                if !state.selection.contains(&seqs[row]) {
+                    state.click_row(&seqs, row, Modifiers::NONE);
+                }
+                copy_selection(ui, state, &filtered_events);
+            } else {
+                action.apply(state, filtered_events[row]);
+            }
+        }
+
+        if let Some(format) = copy_format {
+            state.copy_format = format;
+            copy_selection(ui, state, &filtered_events);
+        }
+
+        let exported = if let Some(format) = export_format {
+            Some(self.export_file(format.extension(), |writer| {
+                format.write(writer, filtered_events.iter().copied())
+            }))
+        } else if export_trace {
+            Some(self.export_file("json", |writer| {
+                export::write_chrome_trace(writer, &self.collector, filtered_events.iter().copied())
+            }))
+        } else {
+            None
+        };
+        if let Some(result) = exported {
+            match result {
+                Ok(path) => self.collector.record(
+                    Level::INFO,
+                    EXPORT_TARGET,
+                    format!(
+                        "exported {} events to {}",
+                        filtered_events.len(),
+                        path.display()
+                    ),
+                ),
+                Err(err) => self.collector.record(
+                    Level::ERROR,
+                    EXPORT_TARGET,
+                    format!("export failed: {err}"),
+                ),
+            }
+        }
+
+        response
+    }
+}
+
+/// `spans` adds the views of recorded spans.
+fn view_switch(ui: &mut Ui, view: &mut View, spans: bool) {
+    if spans || matches!(view, View::Timings | View::Spans) {
+        ui.selectable_value(view, View::Timings, "Timings")
+            .on_hover_text("Busy and Idle Time per Span");
+        ui.selectable_value(view, View::Spans, "Spans")
+            .on_hover_text("Spans as a Tree with Their Events");
+    }
+    ui.selectable_value(view, View::Stats, "Stats")
+        .on_hover_text("Counts and Rates per Level, Target and Callsite");
+    ui.selectable_value(view, View::Patterns, "Patterns")
+        .on_hover_text("Group Events by Message Template");
+    ui.selectable_value(view, View::Events, "Events");
+}
+
+/// Chooses between correlating by span and by one of `keys`.
+fn correlate_menu(ui: &mut Ui, correlate_by: &mut Option<String>, keys: &[String]) {
+    ui.menu_button("Correlate", |ui| {
+        if ui.radio_value(correlate_by, None, "Span").clicked() {
+            ui.close_menu();
+        }
+        // The chosen field stays available when the selection lacks it.
+        let mut keys = keys.to_vec();
+        if let Some(key) = correlate_by.as_ref().filter(|key| !keys.contains(key)) {
+            keys.push(key.clone());
+        }
+        for key in keys {
+            let text = key.clone();
+            if ui.radio_value(correlate_by, Some(key), text).clicked() {
+                ui.close_menu();
+            }
+        }
+    })
+    .response
+    .on_hover_text("Highlight Events Sharing the Selected Event's Span or Field");
+}
+
+fn copy_selection(ui: &Ui, state: &LogsState, filtered_events: &[&CollectedEvent]) {
+    if state.selection.is_empty() {
+        return;
+    }
+
+    let selected = filtered_events
+        .iter()
+        .filter(|event| state.selection.contains(&event.seq))
+        .copied();
+    let text = state.copy_format.format(selected);
+    ui.output_mut(|o| o.copied_text = text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_files_do_not_overwrite_each_other() {
+        let dir = std::env::temp_dir().join(format!("egui_tracing_export_{}", std::process::id()));
+        let logs = Logs::new(EventCollector::new()).export_dir(&dir);
+        let paths = (0..3)
+            .map(|n| logs.export_file("log", |writer| write!(writer, "{n}")))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        let contents = paths
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect::<Vec<_>>();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(contents, ["0", "1", "2"]);
     }
 }
@@ -0,0 +1,296 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Local};
+use egui::{Label, Response, RichText, Ui};
+use tracing::Level;
+
+use super::components::common::CommonProps;
+use super::components::table::Table;
+use super::components::table_cell::TableCell;
+use super::components::table_header::TableHeader;
+use super::state::{LogsState, View};
+use super::style::LogsStyle;
+use crate::tracing::{CollectedEvent, EventCollector};
+
+/// Levels from most to least severe.
+pub const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+/// Replaces the variable parts of a message, such as numbers, UUIDs, hex
+/// values and quoted strings, with placeholders.
+pub fn template(message: &str) -> String {
+    let mut template = String::with_capacity(message.len());
+    let mut chars = message.char_indices().peekable();
+    let mut previous = ' ';
+    while let Some((start, c)) = chars.next() {
+        // An apostrophe inside a word does not start a quoted string.
+        let quote = (c == '"' || c == '\'') && !previous.is_alphanumeric();
+        previous = c;
+        if quote {
+            match message[start + 1..].find(c) {
+                Some(len) => {
+                    template.push(c);
+                    template.push_str("<str>");
+                    template.push(c);
+                    while chars.next_if(|(i, _)| *i <= start + 1 + len).is_some() {}
+                }
+                None => template.push(c),
+            }
+        } else if c.is_alphanumeric() {
+            let mut end = start + c.len_utf8();
+            while let Some((i, c)) =
+                chars.next_if(|(_, c)| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            {
+                end = i + c.len_utf8();
+            }
+            let word = &message[start..end];
+            // Keep sentence punctuation out of the word.
+            let trimmed = word.trim_end_matches('.');
+            // Keep units such as `ms` in `3.5ms`.
+            let number = trimmed.trim_end_matches(|c: char| c.is_alphabetic());
+            match placeholder(trimmed) {
+                Some(placeholder) => template.push_str(placeholder),
+                None if !number.is_empty() && placeholder(number) == Some("<num>") => {
+                    template.push_str("<num>");
+                    template.push_str(&trimmed[number.len()..]);
+                }
+                None => template.push_str(trimmed),
+            }
+            template.push_str(&word[trimmed.len()..]);
+            previous = word.chars().last().unwrap_or(c);
+        } else {
+            template.push(c);
+        }
+    }
+    template
+}
+
+fn placeholder(word: &str) -> Option<&'static str> {
+    let is_hex = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit());
+    let groups = word.split('-').map(str::len).collect::<Vec<_>>();
+    if groups == [8, 4, 4, 4, 12] && word.split('-').all(is_hex) {
+        Some("<uuid>")
+    } else if word.starts_with(|c: char| c.is_ascii_digit()) && word.parse::<f64>().is_ok() {
+        Some("<num>")
+    } else if word.strip_prefix("0x").is_some_and(is_hex)
+        || (word.len() >= 8 && is_hex(word) && word.contains(|c: char| c.is_ascii_digit()))
+    {
+        Some("<hex>")
+    } else {
+        None
+    }
+}
+
+/// Caches the template of each event's message by sequence number.
+#[derive(Debug, Default)]
+pub struct Templates {
+    by_seq: BTreeMap<u64, Arc<str>>,
+    interned: HashSet<Arc<str>>,
+}
+
+impl Templates {
+    pub fn get(&mut self, event: &CollectedEvent) -> Arc<str> {
+        if let Some(template) = self.by_seq.get(&event.seq) {
+            return template.clone();
+        }
+        let template = self::template(event.message().unwrap_or_default());
+        let template = match self.interned.get(template.as_str()) {
+            Some(interned) => interned.clone(),
+            None => {
+                let template: Arc<str> = template.into();
+                self.interned.insert(template.clone());
+                template
+            }
+        };
+        self.by_seq.insert(event.seq, template.clone());
+        template
+    }
+
+    /// Forgets events before `seq`, e.g. after they were evicted.
+    pub fn retain_from(&mut self, seq: u64) {
+        let retained = self.by_seq.split_off(&seq);
+        for template in std::mem::replace(&mut self.by_seq, retained).into_values() {
+            // Only this entry and `interned` still refer to it.
+            if Arc::strong_count(&template) == 2 {
+                self.interned.remove(&template);
+            }
+        }
+    }
+}
+
+pub struct Pattern {
+    pub template: Arc<str>,
+    pub count: u64,
+    /// Counts per level, in the order of [`LEVELS`].
+    pub levels: [u64; 5],
+    pub first: DateTime<Local>,
+    pub last: DateTime<Local>,
+}
+
+/// Groups `events` by template, most frequent first.
+pub fn patterns(events: &[&CollectedEvent], templates: &mut Templates) -> Vec<Pattern> {
+    let mut patterns = HashMap::<Arc<str>, Pattern>::new();
+    for event in events {
+        let template = templates.get(event);
+        let pattern = patterns.entry(template.clone()).or_insert_with(|| Pattern {
+            template,
+            count: 0,
+            levels: [0; 5],
+            first: event.time,
+            last: event.last_time,
+        });
+        pattern.count += event.count;
+        if let Some(i) = LEVELS.iter().position(|level| *level == event.level) {
+            pattern.levels[i] += event.count;
+        }
+        pattern.first = pattern.first.min(event.time);
+        pattern.last = pattern.last.max(event.last_time);
+    }
+    let mut patterns = patterns.into_values().collect::<Vec<_>>();
+    patterns.sort_by(|a, b| b.count.cmp(&a.count).then(a.template.cmp(&b.template)));
+    patterns
+}
+
+/// Shows the patterns of `events`. Clicking a pattern filters the events
+/// view to its instances.
+pub fn show(
+    ui: &mut Ui,
+    state: &mut LogsState,
+    events: &[&CollectedEvent],
+    style: &LogsStyle,
+    collector: &EventCollector,
+) -> Response {
+    let patterns = patterns(events, &mut state.templates);
+    let patterns = patterns.iter().collect::<Vec<_>>();
+    let row_height = ui.text_style_height(&style.text_style) + ui.spacing().item_spacing.y;
+
+    let mut clicked_row = None;
+    let time_format = state.time_format.clone();
+    let response = Table::default()
+        .style(style)
+        .on_clear(|| {
+            collector.clear();
+            state.templates = Templates::default();
+        })
+        .on_row_clicked(|row, _| clicked_row = Some(row))
+        .header(|ui| {
+            for (label, width) in [
+                ("Count", 60.0),
+                ("Levels", 100.0),
+                ("First Seen", 100.0),
+                ("Last Seen", 100.0),
+                ("Pattern", 120.0),
+            ] {
+                TableHeader::default()
+                    .common_props(CommonProps::default().min_width(width))
+                    .children(|ui| {
+                        ui.label(label);
+                    })
+                    .show(ui);
+            }
+        })
+        .toolbar(|ui| super::view_switch(ui, &mut state.view, collector.records_spans()))
+        .row_height(row_height)
+        .row(|ui, _, pattern: &Pattern| {
+            TableCell::default()
+                .common_props(CommonProps::default().min_width(60.0))
+                .children(|ui| {
+                    ui.colored_label(style.text_color, pattern.count.to_string());
+                })
+                .show(ui);
+            TableCell::default()
+                .common_props(CommonProps::default().min_width(100.0))
+                .children(|ui| {
+                    for (level, count) in LEVELS.iter().zip(pattern.levels) {
+                        if count > 0 {
+                            ui.label(
+                                RichText::new(count.to_string())
+                                    .small()
+                                    .color(style.level_color(*level)),
+                            )
+                            .on_hover_text(level.as_str());
+                        }
+                    }
+                })
+                .show(ui);
+            for time in [pattern.first, pattern.last] {
+                TableCell::default()
+                    .common_props(CommonProps::default().min_width(100.0))
+                    .children(|ui| {
+                        ui.colored_label(style.weak_text_color, time_format.format_time(&time));
+                    })
+                    .show(ui);
+            }
+            TableCell::default()
+                .common_props(CommonProps::default().min_width(120.0))
+                .children(|ui| {
+                    ui.style_mut().visuals.override_text_color = Some(style.text_color);
+                    ui.add(Label::new(&*pattern.template).wrap(false))
+                        .on_hover_text(&*pattern.template);
+                })
+                .show(ui);
+        })
+        .show(ui, patterns.iter());
+
+    if let Some(row) = clicked_row {
+        state.pattern = Some(patterns[row].template.to_string());
+        state.view = View::Events;
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_replaces_variable_parts() {
+        assert_eq!(template("took 35 ms"), "took <num> ms");
+        assert_eq!(template("took 3.5ms."), "took <num>ms.");
+        assert_eq!(template("retry -1"), "retry -<num>");
+        assert_eq!(
+            template("user 67e55044-10b1-426f-9247-bb680e5fe0c8 logged in"),
+            "user <uuid> logged in"
+        );
+        assert_eq!(template("at 0x7ffd1e2c"), "at <hex>");
+        assert_eq!(template("commit 4afeb69e"), "commit <hex>");
+        assert_eq!(template("opened \"a b.txt\""), "opened \"<str>\"");
+        assert_eq!(template("key 'abc' missing"), "key '<str>' missing");
+    }
+
+    #[test]
+    fn template_keeps_words() {
+        assert_eq!(template("can't connect"), "can't connect");
+        assert_eq!(template("server_1 started"), "server_1 started");
+        assert_eq!(template("deadbeef failed"), "deadbeef failed");
+        assert_eq!(template("unterminated \"quote"), "unterminated \"quote");
+        assert_eq!(template("v1.2.3"), "v1.2.3");
+    }
+
+    #[test]
+    fn retain_from_forgets_unused_templates() {
+        let mut templates = Templates::default();
+        for (seq, message) in [(0, "a 1"), (1, "b 2"), (2, "a 3")] {
+            let event = CollectedEvent {
+                seq,
+                ..CollectedEvent::synthetic(
+                    Level::INFO,
+                    "test",
+                    message.to_owned(),
+                    &crate::tracing::SystemClock::default(),
+                )
+            };
+            templates.get(&event);
+        }
+        templates.retain_from(2);
+        assert_eq!(templates.by_seq.len(), 1);
+        assert_eq!(templates.interned.len(), 1);
+        assert!(templates.interned.contains("a <num>"));
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use egui::{Align, CollapsingHeader, Layout, Response, RichText, ScrollArea, Ui};
+
+use super::state::{LogsState, View};
+use super::style::LogsStyle;
+use crate::string::Ellipse;
+use crate::tracing::{CollectedEvent, CollectedSpan, EventCollector, SpanRecord};
+
+/// Newest root spans shown, older ones are summarized.
+const MAX_ROOTS: usize = 200;
+/// Events listed per span, the rest are summarized.
+const MAX_EVENTS: usize = 50;
+
+struct Tree<'a> {
+    children: HashMap<u64, Vec<&'a SpanRecord>>,
+    /// Events by the innermost recorded span they were emitted in.
+    events: HashMap<u64, Vec<&'a CollectedEvent>>,
+    now: Duration,
+    style: &'a LogsStyle,
+}
+
+/// Shows the recorded spans as a collapsible tree with their fields,
+/// durations and the filtered events. Clicking "Show Events" filters the events view to
+/// a span.
+pub fn show(
+    ui: &mut Ui,
+    state: &mut LogsState,
+    events: &[&CollectedEvent],
+    style: &LogsStyle,
+    collector: &EventCollector,
+) -> Response {
+    let spans = collector.spans();
+    let mut roots = Vec::new();
+    let mut tree = Tree {
+        children: HashMap::new(),
+        events: HashMap::new(),
+        now: collector.elapsed(),
+        style,
+    };
+    for span in &spans {
+        // Spans whose parent was evicted become roots.
+        match span.parent {
+            Some(parent) if spans.first().is_some_and(|first| parent >= first.seq) => {
+                tree.children.entry(parent).or_default().push(span);
+            }
+            _ => roots.push(span),
+        }
+    }
+    // Spans below the level recorded by the collector have no node, so their events
+    // go to the closest recorded ancestor.
+    let recorded: HashSet<u64> = spans.iter().map(|span| span.seq).collect();
+    for &event in events {
+        let innermost = event
+            .spans
+            .iter()
+            .rev()
+            .find(|span| recorded.contains(&span.id));
+        if let Some(seq) = innermost.map(|span| span.id) {
+            tree.events.entry(seq).or_default().push(event);
+        }
+    }
+
+    let mut clicked = None;
+    let response = ui
+        .vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    style.header_text_color,
+                    format!("{} spans, {} roots", spans.len(), roots.len()),
+                );
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    super::view_switch(ui, &mut state.view, true);
+                });
+            });
+            ui.separator();
+
+            if spans.is_empty() {
+                ui.colored_label(
+                    style.weak_text_color,
+                    if collector.records_spans() {
+                        "No spans recorded yet"
+                    } else {
+                        "Spans are only recorded with EventCollector::record_spans(true)"
+                    },
+                );
+                return;
+            }
+
+            ScrollArea::vertical().show(ui, |ui| {
+                for root in roots.iter().rev().take(MAX_ROOTS) {
+                    node(ui, &tree, root, &mut clicked);
+                }
+                if roots.len() > MAX_ROOTS {
+                    ui.colored_label(
+                        style.weak_text_color,
+                        format!("… {} older root spans", roots.len() - MAX_ROOTS),
+                    );
+                }
+            });
+        })
+        .response;
+
+    if let Some(span) = clicked {
+        state.span = Some(span);
+        state.view = View::Events;
+    }
+    response
+}
+
+fn node(ui: &mut Ui, tree: &Tree, span: &SpanRecord, clicked: &mut Option<(u64, String)>) {
+    let style = tree.style;
+    let children = tree.children.get(&span.seq).map_or(&[][..], Vec::as_slice);
+    let events = tree.events.get(&span.seq).map_or(&[][..], Vec::as_slice);
+    let duration = match span.closed {
+        Some(closed) => format!(
+            "{:.2?} (busy {:.2?})",
+            closed.saturating_sub(span.opened),
+            span.busy
+        ),
+        None => format!("open for {:.2?}", tree.now.saturating_sub(span.opened)),
+    };
+    let name = CollectedSpan {
+        name: span.name.clone(),
+        fields: span.fields.clone(),
+        id: span.seq,
+    }
+    .to_string();
+    let mut label = format!("{}  {duration}", name.clone().truncate_graphemes(80));
+    if !events.is_empty() {
+        label.push_str(&format!("  {} events", events.len()));
+    }
+
+    CollapsingHeader::new(RichText::new(label).color(style.text_color))
+        .id_source(("egui_tracing_span", span.seq))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(style.weak_text_color, &span.target);
+                if ui
+                    .link("Show Events")
+                    .on_hover_text("Show only events inside this span")
+                    .clicked()
+                {
+                    *clicked = Some((span.seq, span.name.clone()));
+                }
+            });
+            for child in children {
+                node(ui, tree, child, clicked);
+            }
+            for event in events.iter().take(MAX_EVENTS) {
+                ui.horizontal(|ui| {
+                    ui.colored_label(style.level_color(event.level), event.level.as_str());
+                    let message = event.message().unwrap_or_default();
+                    let first_line = message.lines().next().unwrap_or_default();
+                    ui.colored_label(
+                        style.text_color,
+                        first_line.to_owned().truncate_graphemes(120),
+                    );
+                });
+            }
+            if events.len() > MAX_EVENTS {
+                ui.colored_label(
+                    style.weak_text_color,
+                    format!("… {} more events", events.len() - MAX_EVENTS),
+                );
+            }
+        });
+}
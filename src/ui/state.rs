@@ -1,11 +1,99 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Local};
+use egui::Modifiers;
 use globset::Glob;
 use serde::{Deserialize, Serialize};
 use tracing::Level;
 
+use crate::export::EventFormat;
+use crate::time::TimeFormat;
+use crate::ui::components::table::RowHeights;
+use crate::ui::correlation::Correlation;
+use crate::ui::keyboard::Movement;
+use crate::ui::patterns::Templates;
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct LogsState {
     pub level_filter: LevelFilter,
     pub target_filter: TargetFilter,
+    pub search: String,
+    pub time_format: TimeFormat,
+    pub field_filters: Vec<(String, String)>,
+    /// Only events whose message has this template are shown.
+    pub pattern: Option<String>,
+    /// Level overrides for targets starting with a prefix; the longest
+    /// matching prefix wins over `level_filter`.
+    #[serde(skip)]
+    pub target_levels: Vec<(String, Level)>,
+    /// Only events from this `file:line` are shown.
+    pub location: Option<String>,
+    /// Only events inside the recorded span with this sequence number and
+    /// name are shown.
+    #[serde(skip)]
+    pub span: Option<(u64, String)>,
+    /// Field correlating events with the selected one, or its span if
+    /// `None`.
+    pub correlate_by: Option<String>,
+    /// Only events correlated like this are shown.
+    #[serde(skip)]
+    pub follow: Option<Correlation>,
+    #[serde(skip)]
+    pub time_window: Option<TimeWindow>,
+    pub selection: BTreeSet<u64>,
+    pub cursor: Option<u64>,
+    pub anchor: Option<u64>,
+    pub copy_format: EventFormat,
+    /// Only events before this sequence number are shown while paused.
+    pub paused: Option<u64>,
+    pub wrap: bool,
+    #[serde(skip)]
+    pub row_heights: RowHeights,
+    #[serde(skip)]
+    pub focused: bool,
+    pub view: View,
+    #[serde(skip)]
+    pub templates: Templates,
+    pub command: String,
+    pub history: Vec<String>,
+    #[serde(skip)]
+    pub history_index: Option<usize>,
+    pub timings: TimingsState,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum View {
+    #[default]
+    Events,
+    Patterns,
+    Stats,
+    Timings,
+    Spans,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TimingsState {
+    pub sort: TimingColumn,
+    /// Sorts from smallest to largest instead.
+    pub ascending: bool,
+    /// Shows idle instead of busy time.
+    pub idle: bool,
+    /// Span name whose histogram is shown.
+    pub selected: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimingColumn {
+    Name,
+    Count,
+    #[default]
+    Total,
+    Mean,
+    Min,
+    Max,
+    P50,
+    P90,
+    P99,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,6 +109,82 @@ pub struct LevelFilter {
 pub struct TargetFilter {
     pub input: String,
     pub targets: Vec<Glob>,
+    pub only: Vec<Glob>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TimeWindow {
+    pub center: DateTime<Local>,
+    pub seconds: i64,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, time: DateTime<Local>) -> bool {
+        (time - self.center).num_milliseconds().abs() <= self.seconds * 1000
+    }
+}
+
+impl LogsState {
+    /// Updates the selection after a click on `row`, where `seqs` maps
+    /// visible rows to event sequence numbers.
+    pub fn click_row(&mut self, seqs: &[u64], row: usize, modifiers: Modifiers) {
+        if modifiers.shift {
+            self.extend_selection(seqs, row);
+        } else if modifiers.command {
+            let seq = seqs[row];
+            if !self.selection.remove(&seq) {
+                self.selection.insert(seq);
+            }
+            self.cursor = Some(seq);
+            self.anchor = Some(seq);
+        } else {
+            self.select_row(seqs, row);
+        }
+    }
+
+    /// Moves the cursor and returns its new row, if there is one.
+    pub fn move_cursor(&mut self, seqs: &[u64], movement: Movement, extend: bool) -> Option<usize> {
+        let last = seqs.len().checked_sub(1)?;
+        let cursor = self
+            .cursor
+            .and_then(|cursor| seqs.binary_search(&cursor).ok());
+        let row = match (movement, cursor) {
+            (Movement::By(delta), Some(row)) => row.saturating_add_signed(delta).min(last),
+            (Movement::By(delta), None) if delta > 0 => 0,
+            (Movement::First, _) => 0,
+            (Movement::By(_) | Movement::Last, _) => last,
+        };
+        if extend {
+            self.extend_selection(seqs, row);
+        } else {
+            self.select_row(seqs, row);
+        }
+        Some(row)
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+        self.cursor = None;
+        self.anchor = None;
+    }
+
+    fn select_row(&mut self, seqs: &[u64], row: usize) {
+        let seq = seqs[row];
+        self.selection = BTreeSet::from([seq]);
+        self.cursor = Some(seq);
+        self.anchor = Some(seq);
+    }
+
+    fn extend_selection(&mut self, seqs: &[u64], row: usize) {
+        let anchor = self
+            .anchor
+            .and_then(|anchor| seqs.binary_search(&anchor).ok())
+            .unwrap_or(row);
+        let range = anchor.min(row)..=anchor.max(row);
+        self.selection = seqs[range].iter().copied().collect();
+        self.cursor = Some(seqs[row]);
+        self.anchor = Some(seqs[anchor]);
+    }
 }
 
 impl Default for LevelFilter {
@@ -45,4 +209,33 @@ impl LevelFilter {
             Level::ERROR => self.error,
         }
     }
+
+    /// Shows `level` and everything more severe, hiding the rest.
+    pub fn at_least(&mut self, level: Level) {
+        self.trace = Level::TRACE <= level;
+        self.debug = Level::DEBUG <= level;
+        self.info = Level::INFO <= level;
+        self.warn = Level::WARN <= level;
+        self.error = Level::ERROR <= level;
+    }
+
+    /// Shows only `level`.
+    pub fn only(&mut self, level: Level) {
+        self.trace = level == Level::TRACE;
+        self.debug = level == Level::DEBUG;
+        self.info = level == Level::INFO;
+        self.warn = level == Level::WARN;
+        self.error = level == Level::ERROR;
+    }
+
+    pub fn toggle(&mut self, level: Level) {
+        let value = match level {
+            Level::TRACE => &mut self.trace,
+            Level::DEBUG => &mut self.debug,
+            Level::INFO => &mut self.info,
+            Level::WARN => &mut self.warn,
+            Level::ERROR => &mut self.error,
+        };
+        *value = !*value;
+    }
 }
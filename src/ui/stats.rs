@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use egui::{Align, Color32, Grid, Layout, Response, RichText, ScrollArea, Ui};
+use globset::Glob;
+use tracing::Level;
+
+use super::patterns::LEVELS;
+use super::state::{LogsState, View};
+use super::style::LogsStyle;
+use crate::tracing::{CollectedEvent, EventCollector};
+
+const TOP: usize = 10;
+/// Rates are measured over this trailing window.
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+struct Count {
+    total: u64,
+    recent: u64,
+}
+
+impl Count {
+    fn add(&mut self, event: &CollectedEvent, since: Duration) {
+        self.total += event.count;
+        if event.last_elapsed >= since {
+            self.recent += event.count;
+        }
+    }
+
+    fn rate(&self) -> f64 {
+        self.recent as f64 / RATE_WINDOW.as_secs_f64()
+    }
+}
+
+enum Click {
+    Level(Level),
+    Target(String),
+    Location(String),
+}
+
+/// Shows totals and rates per level, the noisiest targets and callsites and
+/// the memory used by the collector. Clicking a row filters the events view.
+pub fn show(
+    ui: &mut Ui,
+    state: &mut LogsState,
+    events: &[CollectedEvent],
+    style: &LogsStyle,
+    collector: &EventCollector,
+) -> Response {
+    let since = collector.elapsed().saturating_sub(RATE_WINDOW);
+    let mut levels = HashMap::<Level, Count>::new();
+    let mut targets = HashMap::<&str, Count>::new();
+    let mut locations = HashMap::<String, Count>::new();
+    for event in events {
+        levels.entry(event.level).or_default().add(event, since);
+        targets
+            .entry(event.target.as_str())
+            .or_default()
+            .add(event, since);
+        if let Some(location) = event.location() {
+            locations.entry(location).or_default().add(event, since);
+        }
+    }
+
+    let mut click = None;
+    let response = ui
+        .vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.style_mut().visuals.override_text_color = Some(style.header_text_color);
+                ui.label(format!(
+                    "{} events, {} in memory{}",
+                    events.iter().map(|event| event.count).sum::<u64>(),
+                    format_bytes(collector.memory_usage()),
+                    collector
+                        .max_events_limit()
+                        .map(|max| format!(" (limit {max} events)"))
+                        .unwrap_or_default(),
+                ));
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    super::view_switch(ui, &mut state.view, collector.records_spans());
+                });
+            });
+            ui.separator();
+
+            ScrollArea::vertical().show(ui, |ui| {
+                section(ui, style, "Levels", "Level", |ui| {
+                    for level in LEVELS {
+                        let count = levels.remove(&level).unwrap_or_default();
+                        if row(ui, level.as_str(), &count, Some(style.level_color(level))) {
+                            click = Some(Click::Level(level));
+                        }
+                    }
+                });
+                section(ui, style, "Top Targets", "Target", |ui| {
+                    for (target, count) in top(targets) {
+                        if row(ui, target, &count, None) {
+                            click = Some(Click::Target(target.to_owned()));
+                        }
+                    }
+                });
+                section(ui, style, "Top Callsites", "Callsite", |ui| {
+                    for (location, count) in top(locations) {
+                        if row(ui, &location, &count, None) {
+                            click = Some(Click::Location(location));
+                        }
+                    }
+                });
+            });
+        })
+        .response;
+
+    let Some(click) = click else {
+        return response;
+    };
+    match click {
+        Click::Level(level) => state.level_filter.only(level),
+        Click::Target(target) => {
+            if let Ok(glob) = Glob::new(&globset::escape(&target)) {
+                state.target_filter.only = vec![glob];
+            }
+        }
+        Click::Location(location) => state.location = Some(location),
+    }
+    state.view = View::Events;
+    response
+}
+
+fn section(ui: &mut Ui, style: &LogsStyle, title: &str, name: &str, rows: impl FnOnce(&mut Ui)) {
+    ui.colored_label(style.header_text_color, title);
+    Grid::new(title).striped(true).show(ui, |ui| {
+        ui.colored_label(style.weak_text_color, name);
+        ui.colored_label(style.weak_text_color, "Total");
+        ui.colored_label(style.weak_text_color, "Events/s");
+        ui.end_row();
+        rows(ui);
+    });
+    ui.add_space(8.0);
+}
+
+/// Shows a clickable stats row, returning `true` if it was clicked.
+fn row(ui: &mut Ui, name: &str, count: &Count, color: Option<Color32>) -> bool {
+    let mut text = RichText::new(name);
+    if let Some(color) = color {
+        text = text.color(color);
+    }
+    let clicked = ui
+        .link(text)
+        .on_hover_text("Show only these events")
+        .clicked();
+    ui.label(count.total.to_string());
+    ui.label(format!("{:.1}", count.rate()));
+    ui.end_row();
+    clicked
+}
+
+/// The most frequent keys, with ties in key order so rows don't swap places
+/// between frames.
+fn top<K: Ord>(counts: HashMap<K, Count>) -> Vec<(K, Count)> {
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.1.total.cmp(&a.1.total).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(TOP);
+    counts
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
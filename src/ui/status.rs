@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use egui::{Response, RichText, Sense, Ui, Widget};
+use tracing::Level;
+
+use super::style::LogsStyle;
+use crate::string::Ellipse;
+use crate::time::TimeFormat;
+use crate::tracing::{CollectedEvent, EventCollector};
+
+/// A one-line summary for status bars: the latest event and the number of
+/// warnings and errors since they were last acknowledged by a click.
+pub struct LogStatus {
+    collector: EventCollector,
+    style: Option<LogsStyle>,
+    hover_events: usize,
+}
+
+#[derive(Debug, Default)]
+struct StatusState {
+    revision: u64,
+    /// Warning and error counts when last acknowledged.
+    acknowledged: (u64, u64),
+    recent: VecDeque<CollectedEvent>,
+}
+
+impl LogStatus {
+    #[must_use]
+    pub fn new(collector: EventCollector) -> Self {
+        Self {
+            collector,
+            style: None,
+            hover_events: 5,
+        }
+    }
+
+    #[must_use]
+    pub fn style(mut self, v: LogsStyle) -> Self {
+        self.style = Some(v);
+        self
+    }
+
+    /// Number of recent events shown on hover.
+    #[must_use]
+    pub fn hover_events(mut self, v: usize) -> Self {
+        self.hover_events = v;
+        self
+    }
+}
+
+impl Widget for LogStatus {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let state = ui.memory_mut(|mem| {
+            mem.data
+                .get_temp_mut_or_insert_with(ui.id(), || {
+                    Arc::new(Mutex::new(StatusState::default()))
+                })
+                .clone()
+        });
+        let mut state = state.lock().unwrap();
+
+        // Only events changed since the last frame are looked at.
+        for event in self.collector.events_changed_since(state.revision) {
+            state.revision = state.revision.max(event.revision);
+            // An updated event, e.g. with a higher count, moves to the end.
+            state.recent.retain(|recent| recent.seq != event.seq);
+            state.recent.push_back(event);
+        }
+        let totals = (
+            self.collector.level_count(Level::WARN),
+            self.collector.level_count(Level::ERROR),
+        );
+        let warnings = totals.0.saturating_sub(state.acknowledged.0);
+        let errors = totals.1.saturating_sub(state.acknowledged.1);
+        let keep = self.hover_events.max(1);
+        while state.recent.len() > keep {
+            state.recent.pop_front();
+        }
+
+        let style = self
+            .style
+            .unwrap_or_else(|| LogsStyle::from_visuals(ui.visuals()));
+        let response = ui
+            .horizontal(|ui| {
+                if errors > 0 {
+                    ui.colored_label(style.error_color, format!("✖ {errors}"));
+                }
+                if warnings > 0 {
+                    ui.colored_label(style.warn_color, format!("⚠ {warnings}"));
+                }
+                match state.recent.back() {
+                    Some(event) => {
+                        ui.colored_label(style.level_color(event.level), event.level.as_str());
+                        let message = event.message().unwrap_or_default();
+                        let first_line = message.lines().next().unwrap_or_default();
+                        ui.colored_label(
+                            style.text_color,
+                            first_line.to_owned().truncate_graphemes(80),
+                        );
+                    }
+                    None => {
+                        ui.colored_label(style.weak_text_color, "No events");
+                    }
+                }
+            })
+            .response
+            .interact(Sense::click());
+
+        let response = response.on_hover_ui(|ui| {
+            for event in state.recent.iter().rev() {
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        style.weak_text_color,
+                        TimeFormat::Local.format_time(&event.time),
+                    );
+                    ui.label(
+                        RichText::new(event.level.as_str()).color(style.level_color(event.level)),
+                    );
+                    ui.colored_label(
+                        style.text_color,
+                        event
+                            .message()
+                            .unwrap_or_default()
+                            .to_owned()
+                            .truncate_graphemes(120),
+                    );
+                });
+            }
+            if warnings + errors > 0 {
+                ui.colored_label(style.weak_text_color, "Click to acknowledge");
+            }
+        });
+        if response.clicked() {
+            state.acknowledged = totals;
+        }
+        response
+    }
+}
@@ -0,0 +1,89 @@
+use egui::{Color32, TextStyle, Visuals};
+use tracing::Level;
+
+/// Colors and fonts used by [`Logs`](super::Logs).
+///
+/// By default the style is derived from `ui.visuals()` every frame, so it
+/// follows egui's dark/light mode. Pass a style to
+/// [`Logs::style`](super::Logs::style) to override it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogsStyle {
+    pub trace_color: Color32,
+    pub debug_color: Color32,
+    pub info_color: Color32,
+    pub warn_color: Color32,
+    pub error_color: Color32,
+    /// Color of the message column.
+    pub text_color: Color32,
+    /// Color of secondary columns like time and target.
+    pub weak_text_color: Color32,
+    pub header_text_color: Color32,
+    pub striped: bool,
+    pub stripe_color: Color32,
+    pub selection_color: Color32,
+    /// Background of rows correlated with the selected one.
+    pub highlight_color: Color32,
+    pub text_style: TextStyle,
+}
+
+impl LogsStyle {
+    pub fn dark() -> Self {
+        Self::from_visuals(&Visuals::dark())
+    }
+
+    pub fn light() -> Self {
+        Self::from_visuals(&Visuals::light())
+    }
+
+    pub fn from_visuals(visuals: &Visuals) -> Self {
+        let (trace, debug, info, warn, error) = if visuals.dark_mode {
+            (
+                Color32::from_rgb(117, 80, 123),
+                Color32::from_rgb(114, 159, 207),
+                Color32::from_rgb(78, 154, 6),
+                Color32::from_rgb(196, 160, 0),
+                Color32::from_rgb(204, 0, 0),
+            )
+        } else {
+            (
+                Color32::from_rgb(92, 53, 102),
+                Color32::from_rgb(32, 74, 135),
+                Color32::from_rgb(52, 110, 4),
+                Color32::from_rgb(150, 110, 0),
+                Color32::from_rgb(180, 0, 0),
+            )
+        };
+
+        Self {
+            trace_color: trace,
+            debug_color: debug,
+            info_color: info,
+            warn_color: warn,
+            error_color: error,
+            text_color: visuals.strong_text_color(),
+            weak_text_color: visuals.weak_text_color(),
+            header_text_color: visuals.strong_text_color(),
+            striped: true,
+            stripe_color: visuals.faint_bg_color,
+            selection_color: visuals.selection.bg_fill.linear_multiply(0.5),
+            highlight_color: visuals.selection.bg_fill.linear_multiply(0.2),
+            text_style: TextStyle::Body,
+        }
+    }
+
+    pub fn level_color(&self, level: Level) -> Color32 {
+        match level {
+            Level::TRACE => self.trace_color,
+            Level::DEBUG => self.debug_color,
+            Level::INFO => self.info_color,
+            Level::WARN => self.warn_color,
+            Level::ERROR => self.error_color,
+        }
+    }
+}
+
+impl Default for LogsStyle {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
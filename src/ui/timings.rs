@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use egui::{vec2, Align, Grid, Layout, Rect, Response, RichText, ScrollArea, Sense, Ui};
+
+use super::state::{LogsState, TimingColumn};
+use super::style::LogsStyle;
+use crate::tracing::{DurationStats, EventCollector, SpanStats};
+
+const COLUMNS: [(TimingColumn, &str); 9] = [
+    (TimingColumn::Name, "Span"),
+    (TimingColumn::Count, "Calls"),
+    (TimingColumn::Total, "Total"),
+    (TimingColumn::Mean, "Mean"),
+    (TimingColumn::Min, "Min"),
+    (TimingColumn::Max, "Max"),
+    (TimingColumn::P50, "p50"),
+    (TimingColumn::P90, "p90"),
+    (TimingColumn::P99, "p99"),
+];
+const HISTOGRAM_HEIGHT: f32 = 80.0;
+
+/// Shows busy or idle time statistics per span name with sortable columns
+/// and the histogram of a clicked span.
+pub fn show(
+    ui: &mut Ui,
+    state: &mut LogsState,
+    style: &LogsStyle,
+    collector: &EventCollector,
+) -> Response {
+    let timings = &mut state.timings;
+    let mut spans = collector.span_stats();
+    let idle = timings.idle;
+    spans.sort_by(|a, b| {
+        let ordering = match timings.sort {
+            TimingColumn::Name => a.name.cmp(&b.name),
+            TimingColumn::Count => a.count().cmp(&b.count()),
+            column => value(durations(a, idle), column).cmp(&value(durations(b, idle), column)),
+        };
+        if timings.ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+
+    ui.vertical(|ui| {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut timings.idle, false, "Busy")
+                .on_hover_text("Time Spent Inside the Spans");
+            ui.selectable_value(&mut timings.idle, true, "Idle")
+                .on_hover_text("Time the Spans Were Open but Not Entered");
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                super::view_switch(ui, &mut state.view, true);
+            });
+        });
+        ui.separator();
+
+        if spans.is_empty() {
+            ui.colored_label(
+                style.weak_text_color,
+                if collector.records_spans() {
+                    "No spans closed yet"
+                } else {
+                    "Spans are only timed with EventCollector::record_spans(true)"
+                },
+            );
+            return;
+        }
+
+        ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("timings").striped(true).show(ui, |ui| {
+                for (column, label) in COLUMNS {
+                    let text = if timings.sort == column {
+                        let arrow = if timings.ascending { "⏶" } else { "⏷" };
+                        RichText::new(format!("{label} {arrow}")).strong()
+                    } else {
+                        RichText::new(label)
+                    };
+                    if ui
+                        .button(text.color(style.header_text_color))
+                        .on_hover_text("Sort")
+                        .clicked()
+                    {
+                        if timings.sort == column {
+                            timings.ascending = !timings.ascending;
+                        } else {
+                            timings.sort = column;
+                            timings.ascending = column == TimingColumn::Name;
+                        }
+                    }
+                }
+                ui.end_row();
+
+                for stats in &spans {
+                    let selected = timings.selected.as_ref() == Some(&stats.name);
+                    if ui
+                        .selectable_label(selected, &stats.name)
+                        .on_hover_text("Show Histogram")
+                        .clicked()
+                    {
+                        timings.selected = (!selected).then(|| stats.name.clone());
+                    }
+                    ui.label(stats.count().to_string());
+                    let durations = durations(stats, timings.idle);
+                    for (column, _) in &COLUMNS[2..] {
+                        ui.label(format!("{:.2?}", value(durations, *column)));
+                    }
+                    ui.end_row();
+                }
+            });
+
+            let selected = timings
+                .selected
+                .as_ref()
+                .and_then(|name| spans.iter().find(|stats| &stats.name == name));
+            if let Some(stats) = selected {
+                ui.add_space(8.0);
+                ui.colored_label(
+                    style.header_text_color,
+                    format!(
+                        "{} {} time",
+                        stats.name,
+                        if timings.idle { "idle" } else { "busy" }
+                    ),
+                );
+                histogram(ui, durations(stats, timings.idle), style);
+            }
+        });
+    })
+    .response
+}
+
+fn durations(stats: &SpanStats, idle: bool) -> &DurationStats {
+    if idle {
+        &stats.idle
+    } else {
+        &stats.busy
+    }
+}
+
+fn value(stats: &DurationStats, column: TimingColumn) -> Duration {
+    match column {
+        TimingColumn::Name | TimingColumn::Count => Duration::ZERO,
+        TimingColumn::Total => stats.total,
+        TimingColumn::Mean => stats.mean(),
+        TimingColumn::Min => stats.min,
+        TimingColumn::Max => stats.max,
+        TimingColumn::P50 => stats.percentile(50.0),
+        TimingColumn::P90 => stats.percentile(90.0),
+        TimingColumn::P99 => stats.percentile(99.0),
+    }
+}
+
+fn histogram(ui: &mut Ui, stats: &DurationStats, style: &LogsStyle) {
+    let buckets = stats.histogram();
+    let Some(highest) = buckets.iter().map(|(_, _, count)| *count).max() else {
+        return;
+    };
+    let (rect, response) =
+        ui.allocate_exact_size(vec2(ui.available_width(), HISTOGRAM_HEIGHT), Sense::hover());
+    let width = rect.width() / buckets.len() as f32;
+    let hovered = response.hover_pos();
+    for (index, (start, end, count)) in buckets.iter().enumerate() {
+        let left = rect.left() + index as f32 * width;
+        let bar = Rect::from_min_max(
+            egui::pos2(
+                left,
+                rect.bottom() - rect.height() * *count as f32 / highest as f32,
+            ),
+            egui::pos2(left + (width - 1.0).max(1.0), rect.bottom()),
+        );
+        let column = Rect::from_x_y_ranges(left..=left + width, rect.y_range());
+        let is_hovered = hovered.is_some_and(|pos| column.contains(pos));
+        let color = if is_hovered {
+            style.warn_color
+        } else {
+            style.info_color
+        };
+        ui.painter().rect_filled(bar, 0.0, color);
+        if is_hovered {
+            response
+                .clone()
+                .on_hover_text(format!("{start:.2?} – {end:.2?}: {count}"));
+        }
+    }
+    ui.horizontal(|ui| {
+        ui.colored_label(style.weak_text_color, format!("{:.2?}", stats.min));
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            ui.colored_label(style.weak_text_color, format!("{:.2?}", stats.max));
+        });
+    });
+}
@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use egui::{Align2, Context, Frame, Id, Order, RichText, Sense, Vec2};
+use tracing::Level;
+
+use super::style::LogsStyle;
+use crate::string::Ellipse;
+use crate::tracing::{CollectedEvent, EventCollector};
+
+/// Stacking, auto-expiring notifications for new events at or above a
+/// level, drawn in a corner of the [`Context`].
+pub struct Toasts {
+    collector: EventCollector,
+    id: Id,
+    level: Level,
+    duration: Duration,
+    anchor: Align2,
+    max_toasts: usize,
+    style: Option<LogsStyle>,
+}
+
+#[derive(Debug, Default)]
+struct ToastsState {
+    /// Collector revision up to which events were checked.
+    revision: Option<u64>,
+    toasts: VecDeque<Toast>,
+}
+
+#[derive(Debug)]
+struct Toast {
+    event: CollectedEvent,
+    expires: f64,
+}
+
+impl Toasts {
+    #[must_use]
+    pub fn new(collector: EventCollector) -> Self {
+        Self {
+            collector,
+            id: Id::new("egui_tracing_toasts"),
+            level: Level::WARN,
+            duration: Duration::from_secs(5),
+            anchor: Align2::RIGHT_BOTTOM,
+            max_toasts: 5,
+            style: None,
+        }
+    }
+
+    /// Distinguishes several `Toasts` in the same [`Context`].
+    #[must_use]
+    pub fn id_source(mut self, v: impl std::hash::Hash) -> Self {
+        self.id = Id::new(v);
+        self
+    }
+
+    /// Shows events at `v` or more severe. Defaults to `WARN`.
+    #[must_use]
+    pub fn level(mut self, v: Level) -> Self {
+        self.level = v;
+        self
+    }
+
+    #[must_use]
+    pub fn duration(mut self, v: Duration) -> Self {
+        self.duration = v;
+        self
+    }
+
+    #[must_use]
+    pub fn anchor(mut self, v: Align2) -> Self {
+        self.anchor = v;
+        self
+    }
+
+    #[must_use]
+    pub fn max_toasts(mut self, v: usize) -> Self {
+        self.max_toasts = v;
+        self
+    }
+
+    #[must_use]
+    pub fn style(mut self, v: LogsStyle) -> Self {
+        self.style = Some(v);
+        self
+    }
+
+    /// Shows the toasts, returning the sequence number of a clicked event,
+    /// e.g. for [`Logs::jump_to`](super::Logs::jump_to).
+    pub fn show(self, ctx: &Context) -> Option<u64> {
+        let state = ctx.data_mut(|data| {
+            data.get_temp_mut_or_insert_with(self.id, || {
+                Arc::new(Mutex::new(ToastsState::default()))
+            })
+            .clone()
+        });
+        let mut state = state.lock().unwrap();
+        let now = ctx.input(|i| i.time);
+
+        // Only events collected after the first frame are shown.
+        let revision = *state
+            .revision
+            .get_or_insert_with(|| self.collector.revision());
+        let events = self.collector.events_changed_since(revision);
+        if let Some(latest) = events.iter().map(|event| event.revision).max() {
+            state.revision = Some(latest);
+        }
+        let expires = now + self.duration.as_secs_f64();
+        for event in events.into_iter().filter(|event| event.level <= self.level) {
+            // Repeats and further suppressed events renew the event's toast.
+            state.toasts.retain(|toast| toast.event.seq != event.seq);
+            state.toasts.push_back(Toast { event, expires });
+        }
+        state.toasts.retain(|toast| toast.expires > now);
+        while state.toasts.len() > self.max_toasts {
+            state.toasts.pop_front();
+        }
+        if let Some(first) = state
+            .toasts
+            .iter()
+            .map(|toast| toast.expires)
+            .reduce(f64::min)
+        {
+            ctx.request_repaint_after(Duration::from_secs_f64(first - now));
+        }
+        if state.toasts.is_empty() {
+            return None;
+        }
+
+        let style = self
+            .style
+            .unwrap_or_else(|| LogsStyle::from_visuals(&ctx.style().visuals));
+        let offset = Vec2::new(
+            -self.anchor.x().to_sign() * 8.0,
+            -self.anchor.y().to_sign() * 8.0,
+        );
+        let mut clicked = None;
+        egui::Area::new(self.id)
+            .anchor(self.anchor, offset)
+            .order(Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(320.0);
+                for toast in &state.toasts {
+                    let event = &toast.event;
+                    let response = Frame::popup(ui.style())
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(event.level.as_str())
+                                        .color(style.level_color(event.level))
+                                        .strong(),
+                                );
+                                ui.colored_label(
+                                    style.weak_text_color,
+                                    event.target.truncate_graphemes(32),
+                                );
+                                if event.count > 1 {
+                                    ui.colored_label(
+                                        style.weak_text_color,
+                                        format!("×{}", event.count),
+                                    );
+                                }
+                            });
+                            ui.colored_label(
+                                style.text_color,
+                                event
+                                    .message()
+                                    .unwrap_or_default()
+                                    .to_owned()
+                                    .truncate_graphemes(200),
+                            );
+                        })
+                        .response;
+                    if ui
+                        .interact(response.rect, self.id.with(event.seq), Sense::click())
+                        .on_hover_text("Show in Logs")
+                        .clicked()
+                    {
+                        clicked = Some(event.seq);
+                    }
+                }
+            });
+
+        if let Some(seq) = clicked {
+            state.toasts.retain(|toast| toast.event.seq != seq);
+        }
+        clicked
+    }
+}